@@ -0,0 +1,135 @@
+use std::{
+    fs,
+    time::{Duration, Instant},
+};
+
+use aoc_2022::{day11, day12, day12::Strategy, day13::DataStr, day14::CaveSlice, day15, DAYS};
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion};
+use itertools::Itertools;
+
+/// Parts slower than this get the minimum number of samples, to keep the suite bearable
+const SLOW_PART: Duration = Duration::from_millis(50);
+
+fn read_input(day: u8) -> Option<String> {
+    fs::read_to_string(format!("inputs/day_{}.txt", day)).ok()
+}
+
+fn bench_days(c: &mut Criterion) {
+    for entry in DAYS {
+        let Some(input) = read_input(entry.day) else {
+            eprintln!("skipping day {}: no input", entry.day);
+            continue;
+        };
+
+        let mut group = c.benchmark_group(format!("day_{}", entry.day));
+        group.bench_function("parse", |b| b.iter(|| (entry.parse)(black_box(&input))));
+
+        let parsed = (entry.parse)(&input).unwrap();
+        for (name, solve) in [("part_1", entry.part_1), ("part_2", entry.part_2)] {
+            let start = Instant::now();
+            solve(&*parsed).unwrap();
+            if start.elapsed() > SLOW_PART {
+                group.sample_size(10);
+            }
+
+            group.bench_function(name, |b| b.iter(|| solve(black_box(&*parsed))));
+        }
+        group.finish();
+    }
+}
+
+fn bench_parsing(c: &mut Criterion) {
+    if let Some(input) = read_input(11) {
+        c.bench_function("day_11/parse_monkeys", |b| {
+            b.iter(|| day11::parse_monkeys(black_box(&input)).unwrap())
+        });
+    }
+
+    if let Some(input) = read_input(12) {
+        c.bench_function("day_12/parse_height_map", |b| {
+            b.iter(|| day12::parse_height_map(black_box(&input)).unwrap())
+        });
+    }
+
+    if let Some(input) = read_input(14) {
+        c.bench_function("day_14/parse_cave_slice", |b| {
+            b.iter(|| black_box(&input).parse::<CaveSlice>().unwrap())
+        });
+    }
+
+    if let Some(input) = read_input(15) {
+        c.bench_function("day_15/parse", |b| {
+            b.iter(|| {
+                day15::parse(black_box(&input))
+                    .collect::<Result<Vec<_>, _>>()
+                    .unwrap()
+            })
+        });
+    }
+}
+
+fn bench_internals(c: &mut Criterion) {
+    if let Some(input) = read_input(13) {
+        let pairs = input
+            .lines()
+            .filter(|l| !l.is_empty())
+            .map(|l| DataStr(l.as_bytes()))
+            .tuples::<(_, _)>()
+            .collect_vec();
+
+        c.bench_function("day_13/DataStr::cmp", |b| {
+            b.iter(|| {
+                pairs
+                    .iter()
+                    .map(|(left, right)| black_box(left).cmp(black_box(right)))
+                    .collect_vec()
+            })
+        });
+    }
+
+    if let Some(input) = read_input(14) {
+        c.bench_function("day_14/CaveSlice::simulate_sand", |b| {
+            b.iter_batched(
+                || {
+                    let mut slice = input.parse::<CaveSlice>().unwrap();
+                    slice.insert_floor();
+                    slice
+                },
+                |mut slice| while slice.simulate_sand().is_some() {},
+                BatchSize::LargeInput,
+            )
+        });
+    }
+
+    if let Some(input) = read_input(12) {
+        let height_map = day12::parse_height_map(&input).unwrap();
+
+        c.bench_function("day_12/HeightMap::shortest_path/part_1", |b| {
+            let start = day12::Part1Strategy::start_pos(&height_map);
+            b.iter(|| height_map.shortest_path::<day12::Part1Strategy>(black_box(start)))
+        });
+        c.bench_function("day_12/HeightMap::shortest_path/part_2", |b| {
+            let start = day12::Part2Strategy::start_pos(&height_map);
+            b.iter(|| height_map.shortest_path::<day12::Part2Strategy>(black_box(start)))
+        });
+    }
+
+    if let Some(input) = read_input(11) {
+        let mut group = c.benchmark_group("day_11");
+        group.sample_size(10);
+        group.bench_function("process_monkeys", |b| {
+            b.iter_batched(
+                || day11::parse_monkeys(&input).unwrap(),
+                |mut monkeys| {
+                    let divisors: usize = monkeys.iter().map(|m| m.test_divisor()).product();
+                    day11::process_monkeys(10_000, &mut monkeys, |each| each % divisors)
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench_days, bench_parsing, bench_internals);
+criterion_main!(benches);
@@ -0,0 +1,209 @@
+use std::{fmt::Display, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::ocr;
+
+/// The answer to one part of a puzzle
+///
+/// Answers are compared to the expected answers (which are always written as text) with
+/// [`Answer::matches`], which compares them by what they mean rather than how they are
+/// formatted.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i128),
+    Text(String),
+    /// A picture, which is usually some letters drawn out in pixels
+    ///
+    /// It is displayed as the letters if [`crate::ocr`] can read them, or as the drawing
+    /// otherwise. The alternate format (`{:#}`) always shows the drawing.
+    Grid(PixelGrid),
+}
+
+impl Answer {
+    /// Whether this is the answer written out in `expected`
+    ///
+    /// Numbers match regardless of surrounding whitespace, and text matches regardless of
+    /// trailing whitespace. Grids match either the letters drawn in them or a drawing of
+    /// them using `#` (or `█`) for lit pixels and `.` for unlit ones.
+    pub fn matches(&self, expected: &str) -> bool {
+        match self {
+            Self::Int(answer) => expected.trim().parse() == Ok(*answer),
+            Self::Text(answer) => answer.trim_end() == expected.trim_end(),
+            Self::Grid(grid) => {
+                ocr::read_letters(grid).is_some_and(|letters| letters == expected.trim())
+                    || expected.parse() == Ok(grid.clone())
+            }
+        }
+    }
+}
+
+impl Display for Answer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Int(answer) => write!(f, "{}", answer),
+            Self::Text(answer) => write!(f, "{}", answer),
+            Self::Grid(grid) if f.alternate() => write!(f, "{}", grid),
+            Self::Grid(grid) => match ocr::read_letters(grid) {
+                Some(letters) => write!(f, "{}", letters),
+                None => write!(f, "{}", grid),
+            },
+        }
+    }
+}
+
+macro_rules! from_ints {
+    ($($int:ty),*) => {
+        $(
+            impl From<$int> for Answer {
+                fn from(answer: $int) -> Self {
+                    Self::Int(answer as i128)
+                }
+            }
+        )*
+    };
+}
+
+from_ints!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl From<String> for Answer {
+    fn from(answer: String) -> Self {
+        Self::Text(answer)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(answer: &str) -> Self {
+        Self::Text(answer.to_owned())
+    }
+}
+
+impl From<PixelGrid> for Answer {
+    fn from(grid: PixelGrid) -> Self {
+        Self::Grid(grid)
+    }
+}
+
+/// A rectangle of pixels that are each either lit or unlit
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PixelGrid {
+    width: usize,
+    /// The pixels row by row, starting from the top left
+    pixels: Vec<bool>,
+}
+
+impl PixelGrid {
+    /// Creates a grid with every pixel unlit
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.pixels.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    /// Whether the pixel at `(x, y)` is lit, treating anything outside the grid as unlit
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.width && self.pixels.get(y * self.width + x) == Some(&true)
+    }
+
+    /// Lights the pixel at `(x, y)`, panicking if it is outside the grid
+    pub fn set(&mut self, x: usize, y: usize) {
+        assert!(
+            x < self.width && y < self.height(),
+            "({}, {}) is outside the grid",
+            x,
+            y
+        );
+        self.pixels[y * self.width + x] = true;
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[bool]> {
+        self.pixels.chunks(self.width.max(1))
+    }
+}
+
+impl Display for PixelGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, row) in self.rows().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            for &lit in row {
+                write!(f, "{}", if lit { '#' } else { '.' })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The reason some text couldn't be read as a [`PixelGrid`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotAGrid;
+
+impl FromStr for PixelGrid {
+    type Err = NotAGrid;
+
+    /// Reads a drawing of a grid, ignoring blank lines and any whitespace around each row
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut width = None;
+        let mut pixels = Vec::new();
+
+        for row in text.lines().map(str::trim).filter(|row| !row.is_empty()) {
+            let start = pixels.len();
+            for c in row.chars() {
+                pixels.push(match c {
+                    '#' | '█' => true,
+                    '.' => false,
+                    _ => return Err(NotAGrid),
+                });
+            }
+
+            let row_width = pixels.len() - start;
+            if *width.get_or_insert(row_width) != row_width {
+                return Err(NotAGrid);
+            }
+        }
+
+        Ok(Self {
+            width: width.ok_or(NotAGrid)?,
+            pixels,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compares_answers_by_meaning() {
+        assert!(Answer::from(24000_u32).matches("24000"));
+        assert!(Answer::from(24000_u32).matches(" 24000\n"));
+        assert!(!Answer::from(24000_u32).matches("24000.0"));
+        assert!(Answer::from("CMZ").matches("CMZ\n"));
+        assert!(!Answer::from("CMZ").matches(" CMZ"));
+        assert_ne!(Answer::from(5_u8), Answer::from("5"));
+    }
+
+    #[test]
+    fn draws_and_reads_grids() {
+        let mut grid = PixelGrid::new(3, 2);
+        grid.set(0, 0);
+        grid.set(2, 1);
+
+        assert_eq!(grid.to_string(), "#..\n..#");
+        assert_eq!("#..\n..#\n".parse(), Ok(grid.clone()));
+        assert!(Answer::from(grid.clone()).matches("\n    █..\n    ..█\n    "));
+        assert!(!Answer::from(grid.clone()).matches("#..\n.#."));
+        assert!(!Answer::from(grid).matches("#..\n..#."));
+        assert_eq!("".parse::<PixelGrid>(), Err(NotAGrid));
+    }
+}
@@ -0,0 +1,77 @@
+use std::{collections::HashMap, fs, io, path::Path};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Default)]
+struct InputAnswers {
+    part_1: Option<String>,
+    part_2: Option<String>,
+}
+
+/// Expected answers loaded from a TOML file, keyed by day, then input name
+///
+/// ```toml
+/// [day_9.main]
+/// part_1 = "6236"
+/// part_2 = "2449"
+///
+/// [day_9.example_large]
+/// part_2 = "36"
+/// ```
+#[derive(Deserialize, Debug, Default)]
+#[serde(transparent)]
+pub struct Answers(HashMap<String, HashMap<String, InputAnswers>>);
+
+impl Answers {
+    /// Loads the answers file at `path`, treating a missing file as having no answers
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(err) => return Err(err).with_context(|| format!("could not read {:?}", path)),
+        };
+
+        toml::from_str(&contents).with_context(|| format!("could not parse {:?}", path))
+    }
+
+    pub fn get(&self, day: u8, input: &str, part: u8) -> Option<&str> {
+        let answers = self.0.get(&format!("day_{}", day))?.get(input)?;
+        match part {
+            1 => answers.part_1.as_deref(),
+            2 => answers.part_2.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn looks_up_answers_by_day_input_and_part() {
+        let answers: Answers = toml::from_str(
+            r#"
+            [day_9.main]
+            part_1 = "6236"
+
+            [day_10.demo]
+            part_2 = """
+            #.
+            .#
+            """
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(answers.get(9, "main", 1), Some("6236"));
+        assert_eq!(answers.get(9, "main", 2), None);
+        assert_eq!(answers.get(9, "demo", 1), None);
+        assert_eq!(
+            answers.get(10, "demo", 2),
+            Some("            #.\n            .#\n            ")
+        );
+        assert_eq!(answers.get(11, "main", 1), None);
+    }
+}
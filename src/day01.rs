@@ -1,16 +1,18 @@
+use crate::{answer::Answer, error::parse_number};
+
 pub struct Day1;
 
 fn find_weight_totals(input: &str) -> anyhow::Result<Vec<u32>> {
     let mut weights = vec![0];
 
-    for line in input.split('\n') {
+    for (line_idx, line) in input.split('\n').enumerate() {
         if line.is_empty() {
             weights.push(0);
             continue;
         }
 
         let last_index = weights.len() - 1;
-        weights[last_index] += line.parse::<u32>()?;
+        weights[last_index] += parse_number::<u32>(line_idx, line, line)?;
     }
 
     Ok(weights)
@@ -37,19 +39,24 @@ fn find_largest_three(items: &[u32]) -> (u32, u32, u32) {
 }
 
 impl crate::runner::Day for Day1 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        let weights = find_weight_totals(input)?;
-        Ok(format!("{}", find_largest(&weights)))
+    /// The total weight carried by each elf
+    type Parsed = Vec<u32>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        find_weight_totals(input)
+    }
+
+    fn part_1(weights: &Self::Parsed) -> anyhow::Result<Answer> {
+        Ok(find_largest(weights).into())
     }
 
     fn expected_value_part_1() -> Option<&'static str> {
         Some("71924")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        let weights = find_weight_totals(input)?;
-        let (w1, w2, w3) = find_largest_three(&weights);
-        Ok(format!("{}", w1 + w2 + w3))
+    fn part_2(weights: &Self::Parsed) -> anyhow::Result<Answer> {
+        let (w1, w2, w3) = find_largest_three(weights);
+        Ok((w1 + w2 + w3).into())
     }
 
     fn expected_value_part_2() -> Option<&'static str> {
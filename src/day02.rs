@@ -1,7 +1,9 @@
+use crate::{answer::Answer, error::ParseError};
+
 pub struct Day2;
 
-#[derive(Clone, Copy, PartialEq, Eq)]
-enum Rps {
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rps {
     Rock,
     Paper,
     Scissors,
@@ -24,38 +26,80 @@ impl Rps {
         }
     }
 
-    fn from_char(c: char) -> Self {
+    fn from_char(c: char) -> Option<Self> {
         match c {
-            'A' | 'X' => Self::Rock,
-            'B' | 'Y' => Self::Paper,
-            'C' | 'Z' => Self::Scissors,
-            _ => panic!("Unknown letter {}", c),
+            'A' => Some(Self::Rock),
+            'B' => Some(Self::Paper),
+            'C' => Some(Self::Scissors),
+            _ => None,
         }
     }
 }
 
-fn parse_line_1(input: &str) -> (Rps, Rps) {
-    let mut ipt = input.chars();
-    let first = Rps::from_char(ipt.next().unwrap());
-    ipt.next();
-    let second = Rps::from_char(ipt.next().unwrap());
+/// The second column of the strategy guide, which the two parts read differently
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hint {
+    X,
+    Y,
+    Z,
+}
+
+impl Hint {
+    fn from_char(c: char) -> Option<Self> {
+        match c {
+            'X' => Some(Self::X),
+            'Y' => Some(Self::Y),
+            'Z' => Some(Self::Z),
+            _ => None,
+        }
+    }
+
+    /// Reads the hint as the shape we should play
+    fn as_move(self) -> Rps {
+        match self {
+            Self::X => Rps::Rock,
+            Self::Y => Rps::Paper,
+            Self::Z => Rps::Scissors,
+        }
+    }
+
+    /// Reads the hint as whether we should lose, draw or win, and picks the shape to play
+    /// against `opponent` to get that
+    fn as_outcome(self, opponent: Rps) -> Rps {
+        let opponents = opponent.opponents();
+        match self {
+            Self::X => opponents.2,
+            Self::Y => opponents.1,
+            Self::Z => opponents.0,
+        }
+    }
+}
 
-    (first, second)
+/// Reads the letter at `column` of a line with `read`, failing if it isn't one of `expected`
+fn letter_at<T>(
+    line_idx: usize,
+    line: &str,
+    column: usize,
+    expected: &str,
+    read: impl Fn(char) -> Option<T>,
+) -> Result<T, ParseError> {
+    let found = line.chars().nth(column);
+    found.and_then(read).ok_or_else(|| {
+        ParseError::new(
+            line_idx,
+            column,
+            found.map(String::from).unwrap_or_default(),
+            format!("expected one of {:?}", expected),
+        )
+    })
 }
 
-fn parse_line_2(input: &str) -> (Rps, Rps) {
-    let mut ipt = input.chars();
-    let first = Rps::from_char(ipt.next().unwrap());
-    let opponents = first.opponents();
-    ipt.next();
-    let second = match ipt.next().unwrap() {
-        'X' => opponents.2,
-        'Y' => opponents.1,
-        'Z' => opponents.0,
-        c => panic!("Unknown letter {}", c),
-    };
-
-    (first, second)
+/// Reads the opponent's shape and the hint for what we should do from a line
+fn parse_line(line_idx: usize, input: &str) -> Result<(Rps, Hint), ParseError> {
+    Ok((
+        letter_at(line_idx, input, 0, "ABC", Rps::from_char)?,
+        letter_at(line_idx, input, 2, "XYZ", Hint::from_char)?,
+    ))
 }
 
 fn score_match((left, right): (Rps, Rps)) -> u32 {
@@ -71,29 +115,33 @@ fn score_match((left, right): (Rps, Rps)) -> u32 {
 }
 
 impl crate::runner::Day for Day2 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        Ok(format!(
-            "{}",
-            input
-                .lines()
-                .map(parse_line_1)
-                .map(score_match)
-                .sum::<u32>()
-        ))
+    type Parsed = Vec<(Rps, Hint)>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(input
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| parse_line(idx, line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part_1(rounds: &Self::Parsed) -> anyhow::Result<Answer> {
+        Ok(rounds
+            .iter()
+            .map(|&(opponent, hint)| score_match((opponent, hint.as_move())))
+            .sum::<u32>()
+            .into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("13009")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        Ok(format!(
-            "{}",
-            input
-                .lines()
-                .map(parse_line_2)
-                .map(score_match)
-                .sum::<u32>()
-        ))
+    fn part_2(rounds: &Self::Parsed) -> anyhow::Result<Answer> {
+        Ok(rounds
+            .iter()
+            .map(|&(opponent, hint)| score_match((opponent, hint.as_outcome(opponent))))
+            .sum::<u32>()
+            .into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("10398")
@@ -1,4 +1,4 @@
-use itertools::Itertools;
+use crate::{answer::Answer, error::ParseError};
 
 pub struct Day3;
 
@@ -9,7 +9,7 @@ fn split_at_center(input: &str) -> Vec<String> {
     ]
 }
 
-fn find_common_element(strings: Vec<String>) -> u32 {
+fn find_common_element(strings: Vec<String>) -> anyhow::Result<u32> {
     let mut bits = [0_usize; 53];
 
     for (idx, string) in strings.iter().enumerate() {
@@ -23,47 +23,78 @@ fn find_common_element(strings: Vec<String>) -> u32 {
 
     for (index, &size) in bits.iter().enumerate() {
         if size == strings.len() {
-            return index as u32;
+            return Ok(index as u32);
         }
     }
 
-    panic!("Found no elements common ");
+    anyhow::bail!("no item is in all of {:?}", strings);
+}
+
+/// Checks that every rucksack only contains letters, so that each item has a priority, and
+/// that it can be split into two equally sized compartments
+fn parse_rucksacks(input: &str) -> Result<Vec<&str>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(line_idx, line)| {
+            if let Some((offset, c)) = line.char_indices().find(|(_, c)| !c.is_ascii_alphabetic()) {
+                return Err(ParseError::at(
+                    line_idx,
+                    line,
+                    &line[offset..offset + c.len_utf8()],
+                    "expected an item letter",
+                ));
+            }
+            if line.is_empty() || line.len() % 2 != 0 {
+                return Err(ParseError::at(
+                    line_idx,
+                    line,
+                    line,
+                    "expected an even number of items, at least two",
+                ));
+            }
+            Ok(line)
+        })
+        .collect()
 }
 
 fn priority(c: char) -> u32 {
     match c {
         c @ 'a'..='z' => (c) as u32 - 96,
-        c @ 'A'..='Z' => (c) as u32 - 38,
-        _ => panic!("character out of range"),
+        // anything else will have been rejected by `parse_rucksacks`
+        c => (c) as u32 - 38,
     }
 }
 
 impl crate::runner::Day for Day3 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        Ok(format!(
-            "{}",
-            input
-                .lines()
-                .map(split_at_center)
-                .map(find_common_element)
-                .sum::<u32>()
-        ))
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(parse_rucksacks(input)?
+            .into_iter()
+            .map(str::to_owned)
+            .collect())
+    }
+
+    fn part_1(rucksacks: &Self::Parsed) -> anyhow::Result<Answer> {
+        Ok(rucksacks
+            .iter()
+            .map(|rucksack| split_at_center(rucksack))
+            .map(find_common_element)
+            .sum::<anyhow::Result<u32>>()?
+            .into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("7674")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        Ok(format!(
-            "{}",
-            input
-                .lines()
-                .chunks(3)
-                .into_iter()
-                .map(|c| c.map(|s| s.to_owned()).collect_vec())
-                .map(find_common_element)
-                .sum::<u32>()
-        ))
+    fn part_2(rucksacks: &Self::Parsed) -> anyhow::Result<Answer> {
+        Ok(rucksacks
+            .chunks(3)
+            .map(|c| c.to_vec())
+            .map(find_common_element)
+            .sum::<anyhow::Result<u32>>()?
+            .into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("2805")
@@ -1,24 +1,36 @@
+use crate::{
+    answer::Answer,
+    error::{parse_number, ParseError},
+};
+
 pub struct Day4;
 
-#[derive(Debug, Default)]
-struct Task {
+#[derive(Debug, Clone, Copy)]
+pub struct Task {
     start: u32,
     end: u32,
 }
 
-fn parse_tasks(input: &str) -> (Task, Task) {
-    let mut tasks = <(Task, Task)>::default();
-    let mut task_strings = input.split(',');
+fn parse_task(line_idx: usize, line: &str, task_str: &str) -> Result<Task, ParseError> {
+    let (start, end) = task_str
+        .split_once('-')
+        .ok_or_else(|| ParseError::at(line_idx, line, task_str, "expected a range like 2-4"))?;
 
-    let mut task_1_str = task_strings.next().unwrap().split('-');
-    tasks.0.start = task_1_str.next().unwrap().parse().unwrap();
-    tasks.0.end = task_1_str.next().unwrap().parse().unwrap();
+    Ok(Task {
+        start: parse_number(line_idx, line, start)?,
+        end: parse_number(line_idx, line, end)?,
+    })
+}
 
-    let mut task_2_str = task_strings.next().unwrap().split('-');
-    tasks.1.start = task_2_str.next().unwrap().parse().unwrap();
-    tasks.1.end = task_2_str.next().unwrap().parse().unwrap();
+fn parse_tasks(line_idx: usize, input: &str) -> Result<(Task, Task), ParseError> {
+    let (task_1_str, task_2_str) = input
+        .split_once(',')
+        .ok_or_else(|| ParseError::at(line_idx, input, input, "expected two ranges"))?;
 
-    tasks
+    Ok((
+        parse_task(line_idx, input, task_1_str)?,
+        parse_task(line_idx, input, task_2_str)?,
+    ))
 }
 
 fn is_contained(task1: Task, task2: Task) -> bool {
@@ -32,31 +44,33 @@ fn has_overlap(task1: Task, task2: Task) -> bool {
 }
 
 impl crate::runner::Day for Day4 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        Ok(format!(
-            "{}",
-            input
-                .lines()
-                .map(parse_tasks)
-                .map(|(task1, task2)| is_contained(task1, task2))
-                .map(|each| each as u32)
-                .sum::<u32>()
-        ))
+    type Parsed = Vec<(Task, Task)>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(input
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| parse_tasks(idx, line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part_1(pairs: &Self::Parsed) -> anyhow::Result<Answer> {
+        Ok(pairs
+            .iter()
+            .filter(|&&(task1, task2)| is_contained(task1, task2))
+            .count()
+            .into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("441")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        Ok(format!(
-            "{}",
-            input
-                .lines()
-                .map(parse_tasks)
-                .map(|(task1, task2)| has_overlap(task1, task2))
-                .map(|each| each as u32)
-                .sum::<u32>()
-        ))
+    fn part_2(pairs: &Self::Parsed) -> anyhow::Result<Answer> {
+        Ok(pairs
+            .iter()
+            .filter(|&&(task1, task2)| has_overlap(task1, task2))
+            .count()
+            .into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("861")
@@ -1,48 +1,157 @@
 use itertools::Itertools;
 
+use crate::{
+    answer::Answer,
+    error::{expect_prefix, parse_number, ParseError},
+};
+
 pub struct Day5;
 
-fn parse_structure<'a>(input: &mut impl Iterator<Item = &'a str>) -> Vec<Vec<char>> {
-    let lines = input.take_while(|line| !line.starts_with(" 1"));
+/// Parses one row of crates, drawn as `[A]` with a space between each stack, giving the
+/// crate (if any) in each stack
+fn parse_crate_row(line_idx: usize, line: &str) -> Result<Vec<Option<char>>, ParseError> {
+    if let Some((offset, c)) = line.char_indices().find(|(_, c)| !c.is_ascii()) {
+        return Err(ParseError::at(
+            line_idx,
+            line,
+            &line[offset..offset + c.len_utf8()],
+            "expected a crate or a space",
+        ));
+    }
+
+    line.as_bytes()
+        .chunks(4)
+        .enumerate()
+        .map(|(stack, cell)| match cell {
+            [b'[', c @ b'A'..=b'Z', b']'] | [b'[', c @ b'A'..=b'Z', b']', b' '] => {
+                Ok(Some(*c as char))
+            }
+            [b' ', b' ', b' '] | [b' ', b' ', b' ', b' '] => Ok(None),
+            _ => Err(ParseError::at(
+                line_idx,
+                line,
+                &line[stack * 4..stack * 4 + cell.len()],
+                "expected a crate like \"[A]\" or three spaces",
+            )),
+        })
+        .collect()
+}
 
-    let mut v = Vec::new();
+/// Parses the drawing of the starting stacks, down to and including the line numbering
+/// them, giving each stack bottom first
+fn parse_structure<'a>(
+    input: &str,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<Vec<Vec<char>>, ParseError> {
+    let mut rows = Vec::new();
+
+    let stacks = loop {
+        let Some((line_idx, line)) = lines.next() else {
+            return Err(ParseError::at_end(input, "expected the stack numbers"));
+        };
+        if !line.starts_with(" 1") {
+            rows.push((line_idx, line, parse_crate_row(line_idx, line)?));
+            continue;
+        }
 
-    for line in lines {
-        if (line.len() + 1) / 4 != v.len() {
-            v.resize_with((line.len() + 1) / 4, Vec::new)
+        for (idx, label) in line.split_whitespace().enumerate() {
+            if label != (idx + 1).to_string() {
+                return Err(ParseError::at(
+                    line_idx,
+                    line,
+                    label,
+                    format!("expected stack {}", idx + 1),
+                ));
+            }
+        }
+        break line.split_whitespace().count();
+    };
+
+    let mut v = vec![Vec::new(); stacks];
+    for (line_idx, line, row) in rows.into_iter().rev() {
+        if row.len() > stacks {
+            return Err(ParseError::at(
+                line_idx,
+                line,
+                line,
+                format!("expected at most {} stacks", stacks),
+            ));
         }
 
-        for (idx, char) in line.chars().enumerate() {
-            if let 'A'..='Z' = char {
-                v[((idx + 3) / 4) - 1].push(char)
+        for (stack, item) in row.into_iter().enumerate() {
+            if let Some(item) = item {
+                v[stack].push(item);
             }
         }
     }
 
-    for stack in &mut v {
-        stack.reverse();
+    Ok(v)
+}
+
+fn parse_stack(
+    line_idx: usize,
+    line: &str,
+    part: &str,
+    stacks: usize,
+) -> Result<usize, ParseError> {
+    match parse_number(line_idx, line, part)? {
+        stack @ 1.. if stack <= stacks => Ok(stack),
+        _ => Err(ParseError::at(
+            line_idx,
+            line,
+            part,
+            format!("expected a stack between 1 and {}", stacks),
+        )),
     }
+}
 
-    v
+/// Parses a line of the form `move 3 from 1 to 2`
+fn parse_command(
+    line_idx: usize,
+    line: &str,
+    stacks: usize,
+) -> Result<(u32, usize, usize), ParseError> {
+    let rest = expect_prefix(line_idx, line, "move ")?;
+    let (count, rest) = rest
+        .split_once(" from ")
+        .ok_or_else(|| ParseError::at(line_idx, line, rest, "expected \"<count> from\""))?;
+    let (from, to) = rest
+        .split_once(" to ")
+        .ok_or_else(|| ParseError::at(line_idx, line, rest, "expected \"<stack> to\""))?;
+
+    Ok((
+        parse_number(line_idx, line, count)?,
+        parse_stack(line_idx, line, from, stacks)?,
+        parse_stack(line_idx, line, to, stacks)?,
+    ))
 }
 
-fn parse_commands<'a>(input: &mut impl Iterator<Item = &'a str>) -> Vec<(u32, usize, usize)> {
+/// Parses the moves, checking that none takes more crates than its stack holds by then
+fn parse_commands<'a>(
+    input: &mut impl Iterator<Item = (usize, &'a str)>,
+    stacks: &[Vec<char>],
+) -> Result<Vec<(u32, usize, usize)>, ParseError> {
+    let mut heights = stacks.iter().map(Vec::len).collect::<Vec<_>>();
+
     input
-        .map(|line| {
-            let two_digit = line.as_bytes().get(6) != Some(&b' ');
-            if two_digit {
-                let count = line[5..7].parse().unwrap();
-                let from = line[13..14].parse().unwrap();
-                let to = line[18..19].parse().unwrap();
-
-                (count, from, to)
-            } else {
-                let count = line[5..6].parse().unwrap();
-                let from = line[12..13].parse().unwrap();
-                let to = line[17..18].parse().unwrap();
-
-                (count, from, to)
+        .map(|(line_idx, line)| {
+            let (count, from, to) = parse_command(line_idx, line, stacks.len())?;
+            if count as usize > heights[from - 1] {
+                return Err(ParseError::at(
+                    line_idx,
+                    line,
+                    line,
+                    format!(
+                        "expected at most {} crates, as that is all stack {} holds",
+                        heights[from - 1],
+                        from
+                    ),
+                ));
             }
+            heights[from - 1] -= count as usize;
+            heights[to - 1] += count as usize;
+
+            Ok((count, from, to))
         })
         .collect()
 }
@@ -65,28 +174,47 @@ fn apply_actions_quickly(items: &mut [Vec<char>], commands: &[(u32, usize, usize
     }
 }
 
+/// The crate on top of each stack
+fn top_crates(items: &[Vec<char>]) -> anyhow::Result<Answer> {
+    match items.iter().position(Vec::is_empty) {
+        Some(idx) => anyhow::bail!("stack {} ends up empty", idx + 1),
+        None => Ok(items.iter().map(|v| v.last().unwrap()).join("").into()),
+    }
+}
+
+/// The starting stacks of crates, bottom first, and the moves made with them
+#[derive(Debug)]
+pub struct Procedure {
+    stacks: Vec<Vec<char>>,
+    commands: Vec<(u32, usize, usize)>,
+}
+
 impl crate::runner::Day for Day5 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        let mut input = input.lines().filter(|l| !l.is_empty());
-        let mut items = parse_structure(&mut input);
-        let commands = parse_commands(&mut input);
+    type Parsed = Procedure;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        let mut lines = input.lines().enumerate().filter(|(_, l)| !l.is_empty());
+        let stacks = parse_structure(input, &mut lines)?;
+        let commands = parse_commands(&mut lines, &stacks)?;
 
-        apply_actions_slowly(&mut items, &commands);
+        Ok(Procedure { stacks, commands })
+    }
+
+    fn part_1(procedure: &Self::Parsed) -> anyhow::Result<Answer> {
+        let mut items = procedure.stacks.clone();
+        apply_actions_slowly(&mut items, &procedure.commands);
 
-        Ok(items.iter().map(|v| v.last().unwrap()).join(""))
+        top_crates(&items)
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("QGTHFZBHV")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        let mut input = input.lines().filter(|l| !l.is_empty());
-        let mut items = parse_structure(&mut input);
-        let commands = parse_commands(&mut input);
-
-        apply_actions_quickly(&mut items, &commands);
+    fn part_2(procedure: &Self::Parsed) -> anyhow::Result<Answer> {
+        let mut items = procedure.stacks.clone();
+        apply_actions_quickly(&mut items, &procedure.commands);
 
-        Ok(items.iter().map(|v| v.last().unwrap()).join(""))
+        top_crates(&items)
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("MGDMPSZTM")
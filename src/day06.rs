@@ -1,8 +1,24 @@
+use crate::{answer::Answer, error::ParseError};
+
 pub struct Day6;
 
-fn unique_chars(text: &str) -> bool {
+/// Checks that the signal only contains lowercase letters, so that each has a priority
+fn parse_signal(input: &str) -> Result<&str, ParseError> {
+    let line = input.lines().next().unwrap_or_default();
+    match line.char_indices().find(|(_, c)| !c.is_ascii_lowercase()) {
+        Some((offset, c)) => Err(ParseError::at(
+            0,
+            line,
+            &line[offset..offset + c.len_utf8()],
+            "expected a lowercase letter",
+        )),
+        None => Ok(line),
+    }
+}
+
+fn unique_chars(text: &[u8]) -> bool {
     let mut check_array = [false; 26];
-    for b in text.as_bytes() {
+    for b in text {
         let prio = priority(*b);
         if check_array[prio] {
             return false;
@@ -13,35 +29,39 @@ fn unique_chars(text: &str) -> bool {
     true
 }
 
-fn priority(c: u8) -> usize {
-    match c as char {
-        'a'..='z' => (c - 97).into(),
-        _ => panic!("character out of range"),
+/// Finds how many characters have been received once the last `len` are all different
+fn find_marker(signal: &str, len: usize) -> anyhow::Result<Answer> {
+    match signal.as_bytes().windows(len).position(unique_chars) {
+        Some(index) => Ok((index + len).into()),
+        None => anyhow::bail!(
+            "no {} characters in a row are all different in the {} character signal",
+            len,
+            signal.len()
+        ),
     }
 }
 
+fn priority(c: u8) -> usize {
+    // anything other than a-z will have been rejected by `parse_signal`
+    (c - 97).into()
+}
+
 impl crate::runner::Day for Day6 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        for index in 0..input.len() {
-            if unique_chars(&input[index..(index + 4)]) {
-                return Ok(format!("{}", index + 4));
-            }
-        }
+    type Parsed = String;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(parse_signal(input)?.to_owned())
+    }
 
-        panic!("No solution found");
+    fn part_1(input: &Self::Parsed) -> anyhow::Result<Answer> {
+        find_marker(input, 4)
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("1702")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        for index in 0..input.len() {
-            if unique_chars(&input[index..(index + 14)]) {
-                return Ok(format!("{}", index + 14));
-            }
-        }
-
-        panic!("No solution found");
+    fn part_2(input: &Self::Parsed) -> anyhow::Result<Answer> {
+        find_marker(input, 14)
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("3559")
@@ -1,3 +1,8 @@
+use crate::{
+    answer::Answer,
+    error::{parse_number, ParseError},
+};
+
 pub struct Day7;
 
 #[derive(PartialEq, Eq, Debug)]
@@ -8,29 +13,41 @@ enum Line {
 }
 
 impl Line {
-    fn parse(input: &str) -> Option<Self> {
-        if input.starts_with("$ l") || input.starts_with('d') {
+    fn parse(line_idx: usize, input: &str) -> Result<Option<Self>, ParseError> {
+        Ok(if input.starts_with("$ l") || input.starts_with('d') {
             None
         } else if input == "$ cd .." {
             Some(Self::ChangeUpDir)
         } else if input.starts_with("$ cd") {
             Some(Self::ChangeDir)
         } else {
-            let (nums, _) = input.split_once(' ').unwrap();
-            Some(Self::FoundFile(nums.parse().unwrap()))
-        }
+            let (nums, _) = input.split_once(' ').ok_or_else(|| {
+                ParseError::at(line_idx, input, input, "expected a command or a listing")
+            })?;
+            Some(Self::FoundFile(parse_number(line_idx, input, nums)?))
+        })
     }
 }
 
-fn collect_directory_sizes(input: &str) -> Vec<usize> {
+fn collect_directory_sizes(input: &str) -> Result<Vec<usize>, ParseError> {
     let mut found_directories = Vec::new();
     let mut current_path = Vec::new();
 
-    for line in input.lines().map(Line::parse) {
-        match line {
+    for (line_idx, line) in input.lines().enumerate() {
+        match Line::parse(line_idx, line)? {
             None => {}
             Some(Line::ChangeDir) => current_path.push(0),
-            Some(Line::ChangeUpDir) => found_directories.push(current_path.pop().unwrap()),
+            Some(Line::ChangeUpDir) => match current_path.pop() {
+                Some(size) => found_directories.push(size),
+                None => {
+                    return Err(ParseError::at(
+                        line_idx,
+                        line,
+                        line,
+                        "cannot move up from outside any directory",
+                    ))
+                }
+            },
             Some(Line::FoundFile(size)) => {
                 for path in &mut current_path {
                     *path += size;
@@ -39,39 +56,49 @@ fn collect_directory_sizes(input: &str) -> Vec<usize> {
         }
     }
 
+    if current_path.is_empty() {
+        return Err(ParseError::at_end(
+            input,
+            "expected a `$ cd` into the root directory",
+        ));
+    }
+
     current_path.append(&mut found_directories);
     // the first directory in the list will be the root directory
-    current_path
+    Ok(current_path)
 }
 
 impl crate::runner::Day for Day7 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        Ok(format!(
-            "{}",
-            collect_directory_sizes(input)
-                .iter()
-                .filter(|&size| *size <= 100000)
-                .sum::<usize>()
-        ))
+    /// The size of every directory, starting with the root directory
+    type Parsed = Vec<usize>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(collect_directory_sizes(input)?)
+    }
+
+    fn part_1(directory_sizes: &Self::Parsed) -> anyhow::Result<Answer> {
+        Ok(directory_sizes
+            .iter()
+            .filter(|&size| *size <= 100000)
+            .sum::<usize>()
+            .into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("1783610")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        let directory_sizes = collect_directory_sizes(input);
-
+    fn part_2(directory_sizes: &Self::Parsed) -> anyhow::Result<Answer> {
         let total_size = 70_000_000;
         let required_size = 30_000_000;
         let current_size: usize = directory_sizes[0];
         let to_be_freed = required_size - (total_size - current_size);
 
-        let options = collect_directory_sizes(input)
+        let options = directory_sizes
             .iter()
             .filter(|&size| *size > to_be_freed)
             .fold(total_size, |acc, &size| if acc > size { size } else { acc });
 
-        Ok(format!("{}", options))
+        Ok(options.into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("4370655")
@@ -1,145 +1,97 @@
+use crate::{
+    answer::Answer,
+    error::ParseError,
+    grid::{Grid, Point, ORTHOGONAL},
+};
+
 pub struct Day8;
 
-fn scenic_score(tree_map: &Vec<Vec<u8>>, (x, y): (usize, usize)) -> u32 {
-    let my_size = tree_map[x][y];
-    let mut scenic_score = 1;
+fn parse_tree_map(input: &str) -> Result<Grid<u8>, ParseError> {
+    Grid::parse(input, "a tree height", |_, c| {
+        c.to_digit(10).map(|height| height as u8)
+    })
+}
 
-    let mut sightline = 0;
-    for x_prime in (0..x).rev() {
-        sightline += 1;
+/// Marks the trees that can be seen when looking along a line of trees from outside the grid
+fn look_along(trees: &Grid<u8>, visible: &mut Grid<bool>, mut line: impl Iterator<Item = Point>) {
+    let Some(start) = line.next() else {
+        return;
+    };
+    visible[start] = true;
 
-        if tree_map[x_prime][y] >= my_size {
+    let mut tallest = trees[start];
+    for point in line {
+        if tallest == 9 {
             break;
         }
-    }
-    scenic_score *= sightline;
 
-    let mut sightline = 0;
-    for x_prime in (x + 1)..tree_map.len() {
-        sightline += 1;
-
-        if tree_map[x_prime][y] >= my_size {
-            break;
+        if trees[point] > tallest {
+            tallest = trees[point];
+            visible[point] = true;
         }
     }
-    scenic_score *= sightline;
-
-    let mut sightline = 0;
-    for y_prime in (0..y).rev() {
-        sightline += 1;
+}
 
-        if tree_map[x][y_prime] >= my_size {
-            break;
-        }
-    }
-    scenic_score *= sightline;
+fn scenic_score(trees: &Grid<u8>, point: Point) -> usize {
+    let my_size = trees[point];
 
-    let mut sightline = 0;
-    for y_prime in (y + 1)..tree_map.len() {
-        sightline += 1;
+    ORTHOGONAL
+        .iter()
+        .map(|&direction| {
+            let mut sightline = 0;
+            for tree in trees.ray(point, direction) {
+                sightline += 1;
 
-        if tree_map[x][y_prime] >= my_size {
-            break;
-        }
-    }
-    scenic_score *= sightline;
-
-    scenic_score
+                if trees[tree] >= my_size {
+                    break;
+                }
+            }
+            sightline
+        })
+        .product()
 }
 
 impl crate::runner::Day for Day8 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        let lines = input
-            .lines()
-            .map(|l| l.bytes().map(|b| b - b'0').collect::<Vec<_>>())
-            .collect::<Vec<_>>();
+    /// The height of each tree
+    type Parsed = Grid<u8>;
 
-        let length = lines.len();
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(parse_tree_map(input)?)
+    }
 
-        let mut visibility_map = vec![vec![false; length - 2]; length - 2];
+    fn part_1(trees: &Self::Parsed) -> anyhow::Result<Answer> {
+        let mut visible = trees.map(|_| false);
+        let (left, top) = trees.origin();
+        let (right, bottom) = trees.end();
 
         // horizontal sightlines
-        for x in 1..(length - 1) {
-            let mut minimum = lines[x][0];
-            for y in 1..(length - 1) {
-                if lines[x][y] > minimum {
-                    minimum = lines[x][y];
-                    visibility_map[x - 1][y - 1] = true;
-                }
-
-                if lines[x][y] == 9 {
-                    break;
-                }
-            }
-
-            let mut minimum = lines[x][length - 1];
-            for y in (1..(length - 1)).rev() {
-                if lines[x][y] > minimum {
-                    minimum = lines[x][y];
-                    visibility_map[x - 1][y - 1] = true;
-                }
-
-                if lines[x][y] == 9 {
-                    break;
-                }
-            }
+        for y in top..=bottom {
+            look_along(trees, &mut visible, (left..=right).map(|x| (x, y)));
+            look_along(trees, &mut visible, (left..=right).rev().map(|x| (x, y)));
         }
 
         // vertical sightlines
-        for y in 1..(length - 1) {
-            let mut minimum = lines[0][y];
-            for x in 1..(length - 1) {
-                if lines[x][y] > minimum {
-                    minimum = lines[x][y];
-                    visibility_map[x - 1][y - 1] = true;
-                }
-
-                if lines[x][y] == 9 {
-                    break;
-                }
-            }
-
-            let mut minimum = lines[length - 1][y];
-            for x in (1..(length - 1)).rev() {
-                if lines[x][y] > minimum {
-                    minimum = lines[x][y];
-                    visibility_map[x - 1][y - 1] = true;
-                }
-
-                if lines[x][y] == 9 {
-                    break;
-                }
-            }
+        for x in left..=right {
+            look_along(trees, &mut visible, (top..=bottom).map(|y| (x, y)));
+            look_along(trees, &mut visible, (top..=bottom).rev().map(|y| (x, y)));
         }
 
-        let count = visibility_map.into_iter().flatten().filter(|b| *b).count() + length * 4 - 4;
+        let count = visible.iter().filter(|(_, &visible)| visible).count();
 
-        Ok(count.to_string())
+        Ok(count.into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("1647")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        let lines = input
-            .lines()
-            .map(|l| l.bytes().map(|b| b - b'0').collect::<Vec<_>>())
-            .collect::<Vec<_>>();
-
-        let mut scores = vec![vec![0; lines.len()]; lines.len()];
-
-        for x in 1..(lines.len() - 1) {
-            for y in 1..(lines.len() - 1) {
-                scores[x][y] = scenic_score(&lines, (x, y))
-            }
-        }
-
-        let best_score = scores
-            .into_iter()
-            .flatten()
-            .fold(0, |acc, item| if item > acc { item } else { acc });
+    fn part_2(trees: &Self::Parsed) -> anyhow::Result<Answer> {
+        let best_score = trees
+            .points()
+            .map(|point| scenic_score(trees, point))
+            .max()
+            .unwrap_or(0);
 
-        Ok(best_score.to_string())
+        Ok(best_score.into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("392080")
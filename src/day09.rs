@@ -1,24 +1,40 @@
+use crate::{
+    answer::Answer,
+    error::{parse_number, ParseError},
+    grid::{Grid, Point},
+    runner::Example,
+};
+
 pub struct Day9;
 
-enum Command {
+#[derive(Debug)]
+pub enum Command {
     Up(isize),
     Down(isize),
     Left(isize),
     Right(isize),
 }
 
-fn parse_command(input: &str) -> Command {
-    let count = input[2..].parse().unwrap();
-    match &input[0..1] {
-        "U" => Command::Up(count),
-        "D" => Command::Down(count),
-        "L" => Command::Left(count),
-        "R" => Command::Right(count),
-        _ => panic!("unknown input {}", input),
+fn parse_command(line_idx: usize, input: &str) -> Result<Command, ParseError> {
+    let (direction, count) = input.split_once(' ').ok_or_else(|| {
+        ParseError::at(line_idx, input, input, "expected a direction and a count")
+    })?;
+    let count = parse_number(line_idx, input, count)?;
+    match direction {
+        "U" => Ok(Command::Up(count)),
+        "D" => Ok(Command::Down(count)),
+        "L" => Ok(Command::Left(count)),
+        "R" => Ok(Command::Right(count)),
+        _ => Err(ParseError::at(
+            line_idx,
+            input,
+            direction,
+            "expected one of U, D, L or R",
+        )),
     }
 }
 
-fn follow(prev_pos: &(isize, isize), curr_pos: &mut (isize, isize)) {
+fn follow(prev_pos: &Point, curr_pos: &mut Point) {
     if prev_pos == curr_pos {
         return;
     }
@@ -46,7 +62,7 @@ fn follow(prev_pos: &(isize, isize), curr_pos: &mut (isize, isize)) {
     }
 }
 
-fn follow_chain<const L: usize>(positions: &mut [(isize, isize); L]) {
+fn follow_chain<const L: usize>(positions: &mut [Point; L]) {
     for i in 1..positions.len() {
         let prev_pos = positions[i - 1];
         let curr_pos = &mut positions[i];
@@ -54,76 +70,10 @@ fn follow_chain<const L: usize>(positions: &mut [(isize, isize); L]) {
     }
 }
 
-#[cfg(test)]
-mod tests {
-    use super::follow;
-
-    #[test]
-    fn test_follows_vertical_movement() {
-        let mut curr_pos = (2, 0);
-
-        follow(&(0, 0), &mut curr_pos);
-        assert_eq!(curr_pos, (1, 0));
-
-        follow(&(3, 0), &mut curr_pos);
-        assert_eq!(curr_pos, (2, 0));
-    }
-
-    #[test]
-    fn test_follows_horizontal_movement() {
-        let mut curr_pos = (0, 2);
-
-        follow(&(0, 0), &mut curr_pos);
-        assert_eq!(curr_pos, (0, 1));
-
-        follow(&(0, 3), &mut curr_pos);
-        assert_eq!(curr_pos, (0, 2));
-    }
-
-    #[test]
-    fn test_follows_diagonal_movement() {
-        let mut curr_pos = (1, 2);
-
-        follow(&(0, 0), &mut curr_pos);
-        assert_eq!(curr_pos, (0, 1));
-
-        follow(&(1, 3), &mut curr_pos);
-        assert_eq!(curr_pos, (1, 2));
-
-        follow(&(3, 3), &mut curr_pos);
-        assert_eq!(curr_pos, (2, 3));
-
-        follow(&(4, 2), &mut curr_pos);
-        assert_eq!(curr_pos, (3, 2));
-
-        follow(&(1, 0), &mut curr_pos);
-        assert_eq!(curr_pos, (2, 1));
-    }
-
-    #[test]
-    fn test_doesnt_move_if_items_are_equal() {
-        let mut curr_pos = (1, 2);
-
-        follow(&(1, 2), &mut curr_pos);
-        assert_eq!(curr_pos, (1, 2));
-    }
-
-    #[test]
-    fn test_doesnt_move_if_items_are_too_close() {
-        let mut curr_pos = (1, 2);
-
-        follow(&(1, 3), &mut curr_pos);
-        assert_eq!(curr_pos, (1, 2));
-
-        follow(&(2, 3), &mut curr_pos);
-        assert_eq!(curr_pos, (1, 2));
-    }
-}
-
 fn simulate<const L: usize>(
     commands: &[Command],
     seen_map: &mut SeenMap,
-    positions: &mut [(isize, isize); L],
+    positions: &mut [Point; L],
 ) {
     for command in commands {
         match command {
@@ -160,14 +110,13 @@ fn simulate<const L: usize>(
 }
 
 struct SeenMap {
-    map: Vec<Vec<bool>>,
-    start_pos: (isize, isize),
+    map: Grid<bool>,
     seen: usize,
 }
 
 impl SeenMap {
-    fn mark_seen(&mut self, (x, y): (isize, isize)) {
-        let pos = &mut self.map[(x + self.start_pos.0) as usize][(y + self.start_pos.1) as usize];
+    fn mark_seen(&mut self, pos: Point) {
+        let pos = &mut self.map[pos];
         if !(*pos) {
             self.seen += 1;
         }
@@ -209,35 +158,125 @@ fn build_seen_map(commands: &[Command]) -> SeenMap {
     }
 
     SeenMap {
-        map: vec![
-            vec![false; (max_y - min_y + 2).try_into().unwrap()];
-            (max_x - min_x + 2).try_into().unwrap()
-        ],
-        start_pos: (-min_x + 1, -min_y + 1),
+        map: Grid::with_origin(
+            (min_x, min_y),
+            (max_x - min_x + 1) as usize,
+            (max_y - min_y + 1) as usize,
+            false,
+        ),
         seen: 0,
     }
 }
 
 impl crate::runner::Day for Day9 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
+    type Parsed = Vec<Command>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(input
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| parse_command(idx, line))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn part_1(commands: &Self::Parsed) -> anyhow::Result<Answer> {
         let mut positions = [(0, 0); 2];
-        let commands = input.lines().map(parse_command).collect::<Vec<_>>();
-        let mut seen_map = build_seen_map(&commands);
-        simulate(&commands, &mut seen_map, &mut positions);
-        Ok(seen_map.count_seen().to_string())
+        let mut seen_map = build_seen_map(commands);
+        simulate(commands, &mut seen_map, &mut positions);
+        Ok(seen_map.count_seen().into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("6236")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
+    fn part_2(commands: &Self::Parsed) -> anyhow::Result<Answer> {
         let mut positions = [(0, 0); 10];
-        let commands = input.lines().map(parse_command).collect::<Vec<_>>();
-        let mut seen_map = build_seen_map(&commands);
-        simulate(&commands, &mut seen_map, &mut positions);
-        Ok(seen_map.count_seen().to_string())
+        let mut seen_map = build_seen_map(commands);
+        simulate(commands, &mut seen_map, &mut positions);
+        Ok(seen_map.count_seen().into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("2449")
     }
+
+    fn examples() -> &'static [Example] {
+        &[
+            Example {
+                name: "example_small",
+                part_1: Some("13"),
+                part_2: Some("1"),
+            },
+            Example {
+                name: "example_large",
+                part_1: None,
+                part_2: Some("36"),
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::follow;
+
+    #[test]
+    fn test_follows_vertical_movement() {
+        let mut curr_pos = (2, 0);
+
+        follow(&(0, 0), &mut curr_pos);
+        assert_eq!(curr_pos, (1, 0));
+
+        follow(&(3, 0), &mut curr_pos);
+        assert_eq!(curr_pos, (2, 0));
+    }
+
+    #[test]
+    fn test_follows_horizontal_movement() {
+        let mut curr_pos = (0, 2);
+
+        follow(&(0, 0), &mut curr_pos);
+        assert_eq!(curr_pos, (0, 1));
+
+        follow(&(0, 3), &mut curr_pos);
+        assert_eq!(curr_pos, (0, 2));
+    }
+
+    #[test]
+    fn test_follows_diagonal_movement() {
+        let mut curr_pos = (1, 2);
+
+        follow(&(0, 0), &mut curr_pos);
+        assert_eq!(curr_pos, (0, 1));
+
+        follow(&(1, 3), &mut curr_pos);
+        assert_eq!(curr_pos, (1, 2));
+
+        follow(&(3, 3), &mut curr_pos);
+        assert_eq!(curr_pos, (2, 3));
+
+        follow(&(4, 2), &mut curr_pos);
+        assert_eq!(curr_pos, (3, 2));
+
+        follow(&(1, 0), &mut curr_pos);
+        assert_eq!(curr_pos, (2, 1));
+    }
+
+    #[test]
+    fn test_doesnt_move_if_items_are_equal() {
+        let mut curr_pos = (1, 2);
+
+        follow(&(1, 2), &mut curr_pos);
+        assert_eq!(curr_pos, (1, 2));
+    }
+
+    #[test]
+    fn test_doesnt_move_if_items_are_too_close() {
+        let mut curr_pos = (1, 2);
+
+        follow(&(1, 3), &mut curr_pos);
+        assert_eq!(curr_pos, (1, 2));
+
+        follow(&(2, 3), &mut curr_pos);
+        assert_eq!(curr_pos, (1, 2));
+    }
 }
@@ -1,3 +1,9 @@
+use crate::{
+    answer::{Answer, PixelGrid},
+    error::{parse_number, ParseError},
+    runner::Example,
+};
+
 pub struct Day10;
 
 struct Computer {
@@ -34,24 +40,41 @@ impl Computer {
     }
 }
 
-enum Instruction {
+#[derive(Debug, Clone, Copy)]
+pub enum Instruction {
     Noop,
     Addx(isize),
 }
 
-fn instructions(input: &str) -> impl Iterator<Item = Instruction> + '_ {
-    input.lines().map(|s| match &s[0..1] {
-        "n" => Instruction::Noop,
-        _ => Instruction::Addx(s[5..].parse().unwrap()),
+fn instructions(input: &str) -> impl Iterator<Item = Result<Instruction, ParseError>> + '_ {
+    input.lines().enumerate().map(|(line_idx, line)| {
+        if line == "noop" {
+            Ok(Instruction::Noop)
+        } else if let Some(change) = line.strip_prefix("addx ") {
+            Ok(Instruction::Addx(parse_number(line_idx, line, change)?))
+        } else {
+            Err(ParseError::at(
+                line_idx,
+                line,
+                line,
+                "expected \"noop\" or \"addx <n>\"",
+            ))
+        }
     })
 }
 
 impl crate::runner::Day for Day10 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
+    type Parsed = Vec<Instruction>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(instructions(input).collect::<Result<_, _>>()?)
+    }
+
+    fn part_1(instructions: &Self::Parsed) -> anyhow::Result<Answer> {
         let mut computer = Computer::new();
         let mut signal_strength = 0;
 
-        for instruction in instructions(input) {
+        for &instruction in instructions {
             computer.apply_instruction(instruction, |cpt| match cpt.cycle {
                 20 => signal_strength += 20 * cpt.register,
                 60 => signal_strength += 60 * cpt.register,
@@ -63,20 +86,17 @@ impl crate::runner::Day for Day10 {
             });
         }
 
-        Ok(signal_strength.to_string())
+        Ok(signal_strength.into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("17840")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
+    fn part_2(instructions: &Self::Parsed) -> anyhow::Result<Answer> {
         let mut computer = Computer::new();
-        let mut pixels = [b'.'; 41 * 6];
-        for i in 0..6 {
-            pixels[i * 41 + 40] = b'\n'
-        }
+        let mut screen = PixelGrid::new(40, 6);
 
-        for instruction in instructions(input) {
+        for &instruction in instructions {
             computer.apply_instruction(instruction, |cpt| {
                 let cycle_zero_indexed = cpt.cycle as isize - 1;
                 let horizontal_pos = cycle_zero_indexed % 40;
@@ -86,23 +106,36 @@ impl crate::runner::Day for Day10 {
                     || cpt.register + 1 == horizontal_pos
                     || cpt.register - 1 == horizontal_pos
                 {
-                    pixels[((vertical_pos * 41) + horizontal_pos) as usize] = b'#';
+                    screen.set(horizontal_pos as usize, vertical_pos as usize);
                 }
             });
         }
 
-        let result = String::from_utf8_lossy(&pixels);
-
-        Ok(result.to_string())
+        Ok(screen.into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
-        Some(
-            "####..##..#.....##..#..#.#....###...##..
-#....#..#.#....#..#.#..#.#....#..#.#..#.
-###..#..#.#....#....#..#.#....#..#.#....
-#....####.#....#.##.#..#.#....###..#.##.
-#....#..#.#....#..#.#..#.#....#....#..#.
-####.#..#.####..###..##..####.#.....###.\n",
-        )
+        Some("EALGULPG")
+    }
+
+    fn examples() -> &'static [Example] {
+        &[
+            Example {
+                name: "example_small",
+                part_1: Some("0"),
+                part_2: None,
+            },
+            Example {
+                name: "example_large",
+                part_1: Some("13140"),
+                part_2: Some(
+                    "##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....\n",
+                ),
+            },
+        ]
     }
 }
@@ -1,3 +1,8 @@
+use crate::{
+    answer::Answer,
+    error::{expect_prefix, parse_number, ParseError},
+};
+
 pub struct Day11;
 
 #[derive(Debug, Clone, Copy)]
@@ -7,8 +12,8 @@ enum Operation {
     Add(usize),
 }
 
-#[derive(Debug)]
-struct Monkey {
+#[derive(Debug, Clone)]
+pub struct Monkey {
     items: Vec<usize>,
     operation: Operation,
     inspect_count: usize,
@@ -17,6 +22,12 @@ struct Monkey {
     test_false_id: usize,
 }
 
+impl Monkey {
+    pub fn test_divisor(&self) -> usize {
+        self.test_divisor
+    }
+}
+
 fn apply_operation(operation: Operation, item: usize) -> usize {
     match operation {
         Operation::Square => item * item,
@@ -25,68 +36,110 @@ fn apply_operation(operation: Operation, item: usize) -> usize {
     }
 }
 
-fn parse_operation(input: &str) -> Operation {
-    match &input[0..1] {
-        "+" => Operation::Add(input[2..].parse().unwrap()),
-        "*" => {
-            if &input[2..3] == "o" {
-                Operation::Square
-            } else {
-                Operation::Multiply(input[2..].parse().unwrap())
-            }
-        }
-        _ => panic!("Cannot understand input {:?}", input),
+fn parse_operation(line_idx: usize, line: &str, input: &str) -> Result<Operation, ParseError> {
+    match input.split_once(' ') {
+        Some(("*", "old")) => Ok(Operation::Square),
+        Some(("*", n)) => Ok(Operation::Multiply(parse_number(line_idx, line, n)?)),
+        Some(("+", n)) => Ok(Operation::Add(parse_number(line_idx, line, n)?)),
+        _ => Err(ParseError::at(
+            line_idx,
+            line,
+            input,
+            "expected \"* <n>\", \"+ <n>\" or \"* old\"",
+        )),
     }
 }
 
-fn parse_monkeys(input: &str) -> Vec<Monkey> {
-    let mut lines = input.lines().filter(|each| !each.is_empty());
+/// Takes the next line of a monkey's description, which must start with `prefix`
+fn next_field<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    input: &str,
+    prefix: &str,
+) -> Result<(usize, &'a str, &'a str), ParseError> {
+    let (line_idx, line) = lines
+        .next()
+        .ok_or_else(|| ParseError::at_end(input, format!("expected {:?}", prefix.trim())))?;
+    let rest = expect_prefix(line_idx, line, prefix)?;
+    Ok((line_idx, line, rest))
+}
+
+pub fn parse_monkeys(input: &str) -> Result<Vec<Monkey>, ParseError> {
+    let mut lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, each)| !each.is_empty())
+        .peekable();
     let mut monkeys = Vec::new();
+    // the lines naming where each monkey throws to, checked once we know how many there are
+    let mut targets = Vec::new();
 
-    while let (
-        Some(_),
-        Some(starting_items),
-        Some(operation),
-        Some(test_divisor),
-        Some(test_true_id),
-        Some(test_false_id),
-    ) = (
-        lines.next(),
-        lines.next(),
-        lines.next(),
-        lines.next(),
-        lines.next(),
-        lines.next(),
-    ) {
-        let items = starting_items[18..]
+    while lines.peek().is_some() {
+        next_field(&mut lines, input, "Monkey ")?;
+
+        let (line_idx, line, starting_items) = next_field(&mut lines, input, "  Starting items: ")?;
+        let items = starting_items
             .split(", ")
-            .map(|s| s.parse().unwrap())
-            .collect();
-        let operation = parse_operation(&operation[23..]);
-        let test_divisor = test_divisor[21..].parse().unwrap();
-        let test_true_id = test_true_id[29..].parse().unwrap();
-        let test_false_id = test_false_id[30..].parse().unwrap();
+            .map(|s| parse_number(line_idx, line, s))
+            .collect::<Result<_, _>>()?;
+
+        let (line_idx, line, operation) = next_field(&mut lines, input, "  Operation: new = old ")?;
+        let operation = parse_operation(line_idx, line, operation)?;
+
+        let (line_idx, line, test_divisor) =
+            next_field(&mut lines, input, "  Test: divisible by ")?;
+        let test_divisor = match parse_number(line_idx, line, test_divisor)? {
+            0 => {
+                return Err(ParseError::at(
+                    line_idx,
+                    line,
+                    test_divisor,
+                    "cannot divide by zero",
+                ))
+            }
+            n => n,
+        };
+
+        let test_true = next_field(&mut lines, input, "    If true: throw to monkey ")?;
+        let test_false = next_field(&mut lines, input, "    If false: throw to monkey ")?;
 
         monkeys.push(Monkey {
             items,
             operation,
             inspect_count: 0,
             test_divisor,
-            test_true_id,
-            test_false_id,
+            test_true_id: parse_number(test_true.0, test_true.1, test_true.2)?,
+            test_false_id: parse_number(test_false.0, test_false.1, test_false.2)?,
         });
+        targets.push(test_true);
+        targets.push(test_false);
     }
 
-    monkeys
+    let ids = targets.iter().zip(
+        monkeys
+            .iter()
+            .flat_map(|m| [m.test_true_id, m.test_false_id]),
+    );
+    for ((line_idx, line, part), id) in ids {
+        if id >= monkeys.len() {
+            return Err(ParseError::at(
+                *line_idx,
+                line,
+                part,
+                format!("there are only {} monkeys", monkeys.len()),
+            ));
+        }
+    }
+
+    Ok(monkeys)
 }
 
-fn process_monkeys(rounds: usize, monkeys: &mut [Monkey], on_worry: impl Fn(usize) -> usize) {
+pub fn process_monkeys(rounds: usize, monkeys: &mut [Monkey], on_worry: impl Fn(usize) -> usize) {
     for _ in 0..rounds {
         for idx in 0..monkeys.len() {
             while let Some(item) = monkeys[idx].items.pop() {
                 monkeys[idx].inspect_count += 1;
                 let new_item = on_worry(apply_operation(monkeys[idx].operation, item));
-                if new_item % monkeys[idx].test_divisor == 0 {
+                if new_item.is_multiple_of(monkeys[idx].test_divisor) {
                     monkeys[monkeys[idx].test_true_id].items.push(new_item);
                 } else {
                     monkeys[monkeys[idx].test_false_id].items.push(new_item);
@@ -97,8 +150,14 @@ fn process_monkeys(rounds: usize, monkeys: &mut [Monkey], on_worry: impl Fn(usiz
 }
 
 impl crate::runner::Day for Day11 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        let mut monkeys = parse_monkeys(input);
+    type Parsed = Vec<Monkey>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(parse_monkeys(input)?)
+    }
+
+    fn part_1(monkeys: &Self::Parsed) -> anyhow::Result<Answer> {
+        let mut monkeys = monkeys.clone();
         process_monkeys(
             20,
             &mut monkeys,
@@ -118,14 +177,14 @@ impl crate::runner::Day for Day11 {
                         (h1, h2)
                     }
                 });
-        Ok((best * second_best).to_string())
+        Ok((best * second_best).into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("90882")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        let mut monkeys = parse_monkeys(input);
+    fn part_2(monkeys: &Self::Parsed) -> anyhow::Result<Answer> {
+        let mut monkeys = monkeys.clone();
         let divisors: usize = monkeys.iter().map(|m| m.test_divisor).product();
 
         process_monkeys(
@@ -147,7 +206,7 @@ impl crate::runner::Day for Day11 {
                         (h1, h2)
                     }
                 });
-        Ok((best * second_best).to_string())
+        Ok((best * second_best).into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("30893109657")
@@ -1,174 +1,384 @@
-use std::{collections::VecDeque, marker::PhantomData};
+use std::fmt::Write;
+
+use anyhow::Context;
+
+use crate::{
+    answer::Answer,
+    error::ParseError,
+    grid::{Grid, Point},
+    image::{blend, Rgb},
+    search::{self, Path},
+};
 
 pub struct Day12;
 
-trait Strategy {
-    fn start_pos(height_map: &HeightMap<Self>) -> (usize, usize);
-    fn can_step(height_map: &HeightMap<Self>, start: (usize, usize), end: (usize, usize)) -> bool;
-    fn end_condition(height_map: &HeightMap<Self>, maybe_end: (usize, usize)) -> bool;
+/// How routes are highlighted, and the rest of the map dimmed, when drawn in a terminal
+const PATH_STYLE: &str = "\x1b[1;31m";
+const TERRAIN_STYLE: &str = "\x1b[2m";
+const RESET_STYLE: &str = "\x1b[0m";
+
+/// The colours used when drawing a route over the map as an image
+const LOWEST_RGB: Rgb = [20, 90, 30];
+const HIGHEST_RGB: Rgb = [235, 235, 225];
+const PATH_RGB: Rgb = [220, 40, 40];
+const START_RGB: Rgb = [40, 90, 230];
+const END_RGB: Rgb = [250, 200, 20];
+
+/// The colours used when drawing a distance field as a heatmap
+const NEAREST_RGB: Rgb = [250, 230, 80];
+const FARTHEST_RGB: Rgb = [70, 20, 120];
+const UNREACHABLE_RGB: Rgb = [0, 0, 0];
+
+pub trait Strategy {
+    fn start_pos(height_map: &HeightMap) -> Point;
+    fn can_step(height_map: &HeightMap, start: Point, end: Point) -> bool;
+    fn end_condition(height_map: &HeightMap, maybe_end: Point) -> bool;
 }
 
-struct Part1Strategy;
+pub struct Part1Strategy;
 
 impl Strategy for Part1Strategy {
     #[inline(always)]
-    fn start_pos(height_map: &HeightMap<Self>) -> (usize, usize) {
+    fn start_pos(height_map: &HeightMap) -> Point {
         height_map.start_pos
     }
     #[inline(always)]
-    fn can_step(height_map: &HeightMap<Self>, start: (usize, usize), end: (usize, usize)) -> bool {
-        let current_height = height_map.heights[start.1][start.0];
-        let future_height = height_map.heights[end.1][end.0];
+    fn can_step(height_map: &HeightMap, start: Point, end: Point) -> bool {
+        let current_height = height_map.heights[start];
+        let future_height = height_map.heights[end];
 
         current_height + 1 >= future_height
     }
     #[inline(always)]
-    fn end_condition(height_map: &HeightMap<Self>, maybe_end: (usize, usize)) -> bool {
+    fn end_condition(height_map: &HeightMap, maybe_end: Point) -> bool {
         height_map.end_pos == maybe_end
     }
 }
 
-struct Part2Strategy;
+pub struct Part2Strategy;
 
 impl Strategy for Part2Strategy {
     #[inline(always)]
-    fn start_pos(height_map: &HeightMap<Self>) -> (usize, usize) {
+    fn start_pos(height_map: &HeightMap) -> Point {
         height_map.end_pos
     }
     #[inline(always)]
-    fn can_step(height_map: &HeightMap<Self>, end: (usize, usize), start: (usize, usize)) -> bool {
-        let current_height = height_map.heights[start.1][start.0];
-        let future_height = height_map.heights[end.1][end.0];
+    fn can_step(height_map: &HeightMap, end: Point, start: Point) -> bool {
+        let current_height = height_map.heights[start];
+        let future_height = height_map.heights[end];
 
         current_height + 1 >= future_height
     }
     #[inline(always)]
-    fn end_condition(height_map: &HeightMap<Self>, maybe_end: (usize, usize)) -> bool {
-        height_map.heights[maybe_end.1][maybe_end.0] == 0
+    fn end_condition(height_map: &HeightMap, maybe_end: Point) -> bool {
+        height_map.heights[maybe_end] == 0
     }
 }
 
 #[derive(Debug)]
-struct HeightMap<T: Strategy + ?Sized> {
-    heights: Vec<Vec<u8>>,
-    height: usize,
-    width: usize,
-    start_pos: (usize, usize),
-    end_pos: (usize, usize),
-    _strategy: PhantomData<T>,
+pub struct HeightMap {
+    heights: Grid<u8>,
+    start_pos: Point,
+    end_pos: Point,
 }
 
-impl<T: Strategy> HeightMap<T> {
-    fn neighbours<'a>(
+impl HeightMap {
+    fn neighbours<T: Strategy>(&self, pos: Point) -> impl Iterator<Item = Point> + '_ {
+        self.heights
+            .neighbours4(pos)
+            .filter(move |&neighbour| T::can_step(self, pos, neighbour))
+    }
+
+    /// Finds the shortest path from `start` to wherever the strategy says to end, if any
+    pub fn shortest_path<T: Strategy>(&self, start: Point) -> Option<Path<Point, usize>> {
+        search::bfs(
+            start,
+            |&pos| self.neighbours::<T>(pos),
+            |&pos| T::end_condition(self, pos),
+        )
+    }
+
+    /// Works out how far every square is from the nearest of `sources`
+    ///
+    /// With `Part1Strategy` this is how many steps it takes to climb from a source to each
+    /// square; with `Part2Strategy` it is how many steps it takes to climb from each square
+    /// to a source.
+    pub fn distance_field<T: Strategy>(
         &self,
-        (x, y): (usize, usize),
-        neighbour_space: &'a mut [(usize, usize); 4],
-    ) -> &'a [(usize, usize)] {
-        let mut idx = 0;
-
-        if x > 0 && T::can_step(self, (x, y), (x - 1, y)) {
-            neighbour_space[idx] = (x - 1, y);
-            idx += 1;
+        sources: impl IntoIterator<Item = Point>,
+    ) -> DistanceField {
+        let mut distances = self.heights.map(|_| None);
+        for (pos, distance) in search::bfs_distances(sources, |&pos| self.neighbours::<T>(pos)) {
+            distances[pos] = Some(distance);
         }
 
-        if y > 0 && T::can_step(self, (x, y), (x, y - 1)) {
-            neighbour_space[idx] = (x, y - 1);
-            idx += 1;
+        DistanceField { distances }
+    }
+
+    /// Every square at the lowest elevation, `a` (including the start)
+    pub fn lowest_squares(&self) -> impl Iterator<Item = Point> + '_ {
+        self.heights
+            .iter()
+            .filter(|(_, &height)| height == 0)
+            .map(|(pos, _)| pos)
+    }
+
+    /// The squares at the lowest elevation that have no route up to the end
+    pub fn cut_off_starts(&self) -> Vec<Point> {
+        let field = self.distance_field::<Part2Strategy>([self.end_pos]);
+        self.lowest_squares()
+            .filter(|&pos| !field.is_reachable(pos))
+            .collect()
+    }
+
+    /// The letter a square is marked with on the puzzle's map
+    fn letter(&self, pos: Point) -> char {
+        if pos == self.start_pos {
+            'S'
+        } else if pos == self.end_pos {
+            'E'
+        } else {
+            (b'a' + self.heights[pos]) as char
         }
+    }
 
-        if x < self.width - 1 && T::can_step(self, (x, y), (x + 1, y)) {
-            neighbour_space[idx] = (x + 1, y);
-            idx += 1;
+    /// Draws the map as the puzzle shows it, with `path` drawn over it as arrows pointing
+    /// the way it goes
+    ///
+    /// With `colour`, the path is highlighted with ANSI escape codes and the rest of the
+    /// map is dimmed.
+    pub fn render_path(&self, path: &[Point], colour: bool) -> String {
+        let mut marks = self.heights.map(|_| None);
+        for step in path.windows(2) {
+            marks[step[0]] = Some(arrow(step[0], step[1]));
         }
+        if let Some(&last) = path.last() {
+            marks[last] = Some(self.letter(last));
+        }
+
+        let mut out = String::new();
+        for pos in self.heights.points() {
+            let (mark, style) = match marks[pos] {
+                Some(mark) => (mark, PATH_STYLE),
+                None => (self.letter(pos), TERRAIN_STYLE),
+            };
+            if colour {
+                let _ = write!(out, "{}{}{}", style, mark, RESET_STYLE);
+            } else {
+                out.push(mark);
+            }
 
-        if y < self.height - 1 && T::can_step(self, (x, y), (x, y + 1)) {
-            neighbour_space[idx] = (x, y + 1);
-            idx += 1;
+            if pos.0 == self.heights.end().0 {
+                out.push('\n');
+            }
         }
 
-        &neighbour_space[0..idx]
+        out
     }
 
-    fn steps_between(&self, start: (usize, usize)) -> usize {
-        let mut heap = VecDeque::new();
-        let mut knowledge_map = vec![vec![(None, usize::MAX); self.width]; self.height];
+    /// Colours in the map from green at the lowest to white at the highest, with `path`
+    /// drawn over it in red
+    pub fn path_image(&self, path: &[Point]) -> Grid<Rgb> {
+        let mut pixels = self
+            .heights
+            .map(|&height| blend(LOWEST_RGB, HIGHEST_RGB, height as f64 / 25.0));
+        for &pos in path {
+            pixels[pos] = PATH_RGB;
+        }
+        pixels[self.start_pos] = START_RGB;
+        pixels[self.end_pos] = END_RGB;
 
-        heap.push_back(start);
-        knowledge_map[start.1][start.0].1 = 0;
+        pixels
+    }
+}
 
-        let mut neighbour_space = [(0, 0); 4];
+/// How many steps each square of a height map is from some set of sources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DistanceField {
+    /// The distance to each square, or `None` if it can't be reached
+    distances: Grid<Option<usize>>,
+}
 
-        while let Some(pos) = heap.pop_front() {
-            if T::end_condition(self, pos) {
-                return knowledge_map[pos.1][pos.0].1;
-            }
+impl DistanceField {
+    /// The distance to `pos`, or `None` if it can't be reached or is off the map
+    pub fn get(&self, pos: Point) -> Option<usize> {
+        self.distances.get(pos).copied().flatten()
+    }
+
+    pub fn is_reachable(&self, pos: Point) -> bool {
+        self.get(pos).is_some()
+    }
+
+    /// Every square along with its distance, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, Option<usize>)> + '_ {
+        self.distances
+            .iter()
+            .map(|(pos, &distance)| (pos, distance))
+    }
+
+    pub fn unreachable(&self) -> impl Iterator<Item = Point> + '_ {
+        self.iter()
+            .filter(|(_, distance)| distance.is_none())
+            .map(|(pos, _)| pos)
+    }
+
+    /// The distance to the farthest square that can be reached
+    pub fn farthest(&self) -> Option<usize> {
+        self.iter().filter_map(|(_, distance)| distance).max()
+    }
 
-            for &neighbour in self.neighbours(pos, &mut neighbour_space) {
-                let potential_score = knowledge_map[pos.1][pos.0].1 + 1;
-                if potential_score < knowledge_map[neighbour.1][neighbour.0].1 {
-                    knowledge_map[neighbour.1][neighbour.0] = (Some(pos), potential_score);
-                    heap.push_back(neighbour);
-                }
+    /// How many squares are at each distance, indexed by distance
+    pub fn histogram(&self) -> Vec<usize> {
+        let mut counts = vec![0; self.farthest().map_or(0, |farthest| farthest + 1)];
+        for (_, distance) in self.iter() {
+            if let Some(distance) = distance {
+                counts[distance] += 1;
             }
         }
+        counts
+    }
 
-        panic!("Path not found!")
+    /// Colours each square from yellow for the nearest to purple for the farthest, with
+    /// unreachable squares in black
+    pub fn heatmap(&self) -> Grid<Rgb> {
+        let farthest = self.farthest().unwrap_or(0).max(1) as f64;
+        self.distances.map(|distance| match distance {
+            Some(distance) => blend(NEAREST_RGB, FARTHEST_RGB, *distance as f64 / farthest),
+            None => UNREACHABLE_RGB,
+        })
     }
 }
 
-fn parse_height_map<T: Strategy>(input: &str) -> HeightMap<T> {
-    let mut start_pos = (0, 0);
-    let mut end_pos = (0, 0);
-
-    let heights = input
-        .lines()
-        .enumerate()
-        .map(|(y, line)| {
-            line.as_bytes()
-                .iter()
-                .enumerate()
-                .map(|(x, b)| match *b {
-                    b'S' => {
-                        start_pos = (x, y);
-                        0
-                    }
-                    b'E' => {
-                        end_pos = (x, y);
-                        25
-                    }
-                    b => b - 97,
-                })
-                .collect()
-        })
-        .collect::<Vec<Vec<u8>>>();
+fn arrow(from: Point, to: Point) -> char {
+    match (to.0 - from.0, to.1 - from.1) {
+        (0, -1) => '^',
+        (1, 0) => '>',
+        (0, 1) => 'v',
+        (-1, 0) => '<',
+        _ => '*',
+    }
+}
 
-    let height = heights.len();
-    let width = heights[0].len();
+pub fn parse_height_map(input: &str) -> Result<HeightMap, ParseError> {
+    let mut start_pos = None;
+    let mut end_pos = None;
 
-    HeightMap {
+    let heights = Grid::parse(input, "a height from a to z, S or E", |pos, c| match c {
+        'S' => {
+            start_pos = Some(pos);
+            Some(0)
+        }
+        'E' => {
+            end_pos = Some(pos);
+            Some(25)
+        }
+        'a'..='z' => Some(c as u8 - b'a'),
+        _ => None,
+    })?;
+
+    Ok(HeightMap {
         heights,
-        height,
-        width,
-        start_pos,
-        end_pos,
-        _strategy: PhantomData,
-    }
+        start_pos: start_pos.ok_or_else(|| ParseError::at_end(input, "expected a start (S)"))?,
+        end_pos: end_pos.ok_or_else(|| ParseError::at_end(input, "expected an end (E)"))?,
+    })
 }
 
 impl crate::runner::Day for Day12 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        let height_map = parse_height_map::<Part1Strategy>(input);
-        Ok(height_map.steps_between(height_map.start_pos).to_string())
+    type Parsed = HeightMap;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(parse_height_map(input)?)
+    }
+
+    fn part_1(height_map: &Self::Parsed) -> anyhow::Result<Answer> {
+        let path = height_map
+            .shortest_path::<Part1Strategy>(Part1Strategy::start_pos(height_map))
+            .context("there is no path from the start to the end")?;
+
+        Ok(path.steps().into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("339")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        let height_map = parse_height_map::<Part2Strategy>(input);
-        Ok(height_map.steps_between(height_map.end_pos).to_string())
+    fn part_2(height_map: &Self::Parsed) -> anyhow::Result<Answer> {
+        let path = height_map
+            .shortest_path::<Part2Strategy>(Part2Strategy::start_pos(height_map))
+            .context("there is no path from the end to the lowest ground")?;
+
+        Ok(path.steps().into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("332")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEMO: &str = "\
+Sabqponm
+abcryxxl
+accszExk
+acctuvwj
+abdefghi
+";
+
+    #[test]
+    fn draws_the_route_over_the_map() {
+        let height_map = parse_height_map(DEMO).unwrap();
+        let path = height_map
+            .shortest_path::<Part1Strategy>(Part1Strategy::start_pos(&height_map))
+            .unwrap();
+
+        assert_eq!(path.steps(), 31);
+        assert_eq!(
+            height_map.render_path(&path.nodes, false),
+            "\
+>>vv<<<<
+abvvv<<^
+acvv>E^^
+acv>>>^^
+ab>>>>>^
+"
+        );
+
+        let pixels = height_map.path_image(&path.nodes);
+        assert_eq!(pixels[(0, 0)], START_RGB);
+        assert_eq!(pixels[(1, 0)], PATH_RGB);
+        assert_eq!(pixels[(0, 4)], LOWEST_RGB);
+    }
+
+    #[test]
+    fn measures_distances_across_the_map() {
+        let height_map = parse_height_map(DEMO).unwrap();
+
+        let forwards = height_map.distance_field::<Part1Strategy>([height_map.start_pos]);
+        assert_eq!(forwards.get(height_map.end_pos), Some(31));
+        assert_eq!(forwards.unreachable().count(), 0);
+        assert_eq!(forwards.histogram().iter().sum::<usize>(), 40);
+
+        let reversed = height_map.distance_field::<Part2Strategy>([height_map.end_pos]);
+        let closest_start = height_map
+            .lowest_squares()
+            .filter_map(|pos| reversed.get(pos))
+            .min();
+        assert_eq!(closest_start, Some(29));
+        assert_eq!(reversed.get((0, 0)), Some(31));
+        assert!(height_map.cut_off_starts().is_empty());
+        assert_eq!(reversed.heatmap()[height_map.end_pos], NEAREST_RGB);
+    }
+
+    #[test]
+    fn finds_starts_that_are_cut_off() {
+        // the `a` next to the end is walled in by higher ground
+        let input = format!("Sbcdefghijklmnopqrstuvwxyz\n{}aE\n", "z".repeat(24));
+        let height_map = parse_height_map(&input).unwrap();
+
+        assert_eq!(height_map.cut_off_starts(), vec![(24, 1)]);
+        let field = height_map.distance_field::<Part2Strategy>([height_map.end_pos]);
+        assert_eq!(field.unreachable().collect::<Vec<_>>(), vec![(24, 1)]);
+        assert_eq!(field.heatmap()[(24, 1)], UNREACHABLE_RGB);
+    }
+}
@@ -2,14 +2,61 @@ use std::cmp::Ordering;
 
 use itertools::Itertools;
 
+use crate::{answer::Answer, error::ParseError};
+
 pub struct Day13;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct DataStr<'a>(&'a [u8]);
+pub struct DataStr<'a>(pub &'a [u8]);
 
 impl<'a> DataStr<'a> {
-    fn from_str(str: &'a str) -> Self {
-        Self(str.as_bytes())
+    /// Checks that a line is a well-formed packet before comparing it
+    fn parse(line_idx: usize, line: &'a str) -> Result<Self, ParseError> {
+        let error = |idx: usize, len: usize, message: &str| {
+            let text = line.get(idx..idx + len).unwrap_or_default();
+            ParseError::new(line_idx, idx, text, message)
+        };
+
+        if !line.starts_with('[') {
+            return Err(error(0, 1, "expected a packet starting with '['"));
+        }
+
+        let bytes = line.as_bytes();
+        let mut depth = 0;
+        let mut idx = 0;
+        while idx < bytes.len() {
+            match bytes[idx] {
+                _ if depth == 0 && idx > 0 => {
+                    return Err(error(
+                        idx,
+                        line.len() - idx,
+                        "unexpected text after the packet",
+                    ))
+                }
+                b'[' => depth += 1,
+                b']' => depth -= 1,
+                b',' => {}
+                b'0'..=b'9' => {
+                    let len = bytes[idx..]
+                        .iter()
+                        .take_while(|b| b.is_ascii_digit())
+                        .count();
+                    if !matches!(&bytes[idx..idx + len], [_] | b"10") {
+                        return Err(error(idx, len, "expected a number from 0 to 10"));
+                    }
+                    idx += len;
+                    continue;
+                }
+                _ => return Err(error(idx, 1, "expected a list or a number")),
+            }
+            idx += 1;
+        }
+
+        if depth > 0 {
+            return Err(error(line.len(), 0, "expected ']' to close the list"));
+        }
+
+        Ok(Self(bytes))
     }
 
     fn get_int(&'a self, idx: usize) -> (u8, usize) {
@@ -86,36 +133,55 @@ impl<'a> Ord for DataStr<'a> {
     }
 }
 
+fn parse_packets(input: &str) -> Result<Vec<DataStr<'_>>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(idx, line)| DataStr::parse(idx, line))
+        .collect()
+}
+
 impl crate::runner::Day for Day13 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        let sum = input
-            .lines()
-            .filter(|l| !l.is_empty())
-            .map(DataStr::from_str)
+    /// Every packet, which have been checked to be well-formed, in pairs
+    type Parsed = Vec<String>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        let packets = parse_packets(input)?;
+        if packets.len() % 2 == 1 {
+            let message = "expected a packet to pair with the last one";
+            return Err(ParseError::at_end(input, message).into());
+        }
+
+        Ok(packets
+            .into_iter()
+            .map(|packet| String::from_utf8_lossy(packet.0).into_owned())
+            .collect())
+    }
+
+    fn part_1(packets: &Self::Parsed) -> anyhow::Result<Answer> {
+        let sum = packets
+            .iter()
+            .map(|packet| DataStr(packet.as_bytes()))
             .tuples()
             .enumerate()
             .filter(|(_, (left, right))| left < right)
             .map(|(idx, _)| idx + 1)
             .sum::<usize>();
-        Ok(sum.to_string())
+        Ok(sum.into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("5675")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
+    fn part_2(packets: &Self::Parsed) -> anyhow::Result<Answer> {
         let divider_2 = DataStr(b"[[2]]");
         let divider_6 = DataStr(b"[[6]]");
 
         let mut divider_2_pos = 1;
         let mut divider_6_pos = 2; // always comes after div2
 
-        let lines = input
-            .lines()
-            .filter(|l| !l.is_empty())
-            .map(DataStr::from_str);
-
-        for line in lines {
+        for line in packets.iter().map(|packet| DataStr(packet.as_bytes())) {
             if line < divider_2 {
                 divider_2_pos += 1;
                 divider_6_pos += 1;
@@ -124,7 +190,7 @@ impl crate::runner::Day for Day13 {
             }
         }
 
-        Ok((divider_2_pos * divider_6_pos).to_string())
+        Ok((divider_2_pos * divider_6_pos).into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("20383")
@@ -191,6 +257,15 @@ mod tests {
         assert!(DataStr(b"[[[]]]") > DataStr(b"[[]]"),);
     }
 
+    #[test]
+    fn packets_must_be_well_formed() {
+        assert!(DataStr::parse(0, "[1,[2,10]]").is_ok());
+        assert_eq!(DataStr::parse(0, "[1,[2]").unwrap_err().column, 7);
+        assert_eq!(DataStr::parse(0, "[1]]").unwrap_err().column, 4);
+        assert_eq!(DataStr::parse(0, "[1,11]").unwrap_err().text, "11");
+        assert_eq!(DataStr::parse(0, "[a]").unwrap_err().column, 2);
+    }
+
     #[test]
     fn data_str_buggy_case() {
         assert!(DataStr(b"[[[10,[6,6]]]]") > DataStr(b"[[10,[[9,10,0],2]]]]"));
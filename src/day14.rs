@@ -1,55 +1,60 @@
 use std::{
     cmp,
-    fmt::{Display, Write},
-    ops::{Index, IndexMut},
+    fmt::{Debug, Display, Write},
     str::FromStr,
 };
 
 use itertools::Itertools;
 
+use crate::{
+    answer::Answer,
+    error::{parse_number, ParseError},
+    grid::{Grid, Point},
+};
+
 pub struct Day14;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-enum CaveSliceElement {
+pub enum CaveSliceElement {
     Sand,
     Rock,
     Air,
     Source,
 }
 
-#[derive(Debug)]
-struct CaveSlice {
-    x_start: usize,
-    y_start: usize,
+impl Display for CaveSliceElement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_char(match self {
+            CaveSliceElement::Sand => 'o',
+            CaveSliceElement::Rock => '█',
+            CaveSliceElement::Air => ' ',
+            CaveSliceElement::Source => '+',
+        })
+    }
+}
 
-    cells: Vec<Vec<CaveSliceElement>>,
+#[derive(Clone)]
+pub struct CaveSlice {
+    cells: Grid<CaveSliceElement>,
 }
 
 impl CaveSlice {
-    fn get(&self, (x, y): (usize, usize)) -> Option<&CaveSliceElement> {
-        let x = x - self.x_start;
-        let y = y - self.y_start;
-        if y >= self.cells.len() || x >= self.cells[0].len() {
-            None
-        } else {
-            Some(&self.cells[y][x])
-        }
+    pub fn get(&self, point: Point) -> Option<&CaveSliceElement> {
+        self.cells.get(point)
     }
 
-    fn insert_floor(&mut self) {
-        self.cells
-            .push(vec![CaveSliceElement::Air; self.cells[0].len()]);
-        self.cells
-            .push(vec![CaveSliceElement::Rock; self.cells[0].len()]);
+    pub fn insert_floor(&mut self) {
+        self.cells.push_row(CaveSliceElement::Air);
+        self.cells.push_row(CaveSliceElement::Rock);
     }
 
     /// Simulates the falling of a grain of sand from the source, until it can go no further
     ///
     /// Returns Some((x, y)) of the final location if the sand stops
     /// Returns None if the sand falls out of bounds
-    fn simulate_sand(&mut self) -> Option<(usize, usize)> {
+    pub fn simulate_sand(&mut self) -> Option<Point> {
         let mut sand_point = (500, 0);
-        if self[sand_point] != CaveSliceElement::Source {
+        if self.cells[sand_point] != CaveSliceElement::Source {
             // cave is blocked, nothing can come in anyway
             return None;
         }
@@ -68,46 +73,28 @@ impl CaveSlice {
 
             // sand couldn't find anywhere better to go, so we've found
             // the end of the road
-            self[sand_point] = CaveSliceElement::Sand;
+            self.cells[sand_point] = CaveSliceElement::Sand;
             return Some(sand_point);
         }
     }
 }
 
-impl Index<(usize, usize)> for CaveSlice {
-    type Output = CaveSliceElement;
-
-    fn index(&self, (x, y): (usize, usize)) -> &Self::Output {
-        &self.cells[y - self.y_start][x - self.x_start]
-    }
-}
-
-impl IndexMut<(usize, usize)> for CaveSlice {
-    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
-        &mut self.cells[y - self.y_start][x - self.x_start]
-    }
-}
-
 impl FromStr for CaveSlice {
-    type Err = String;
+    type Err = ParseError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
         let mut x_size = (500, 500);
         let mut y_size = (0, 0);
         let mut commands = Vec::new();
 
-        for row in s.lines() {
+        for (line_idx, row) in s.lines().enumerate() {
             let mut current_commands = Vec::new();
             for pair in row.split(" -> ") {
-                let (x, y) = pair
-                    .split_once(',')
-                    .ok_or(format!("could not split row {row:?}"))?;
-                let x = x
-                    .parse()
-                    .map_err(|_| format!("could not parse string {x} as int"))?;
-                let y = y
-                    .parse()
-                    .map_err(|_| format!("could not parse string {y} as int"))?;
+                let (x, y) = pair.split_once(',').ok_or_else(|| {
+                    ParseError::at(line_idx, row, pair, "expected a point like 498,4")
+                })?;
+                let x = parse_number(line_idx, row, x)?;
+                let y = parse_number(line_idx, row, y)?;
                 if x > x_size.1 {
                     x_size.1 = x;
                 }
@@ -125,21 +112,19 @@ impl FromStr for CaveSlice {
             commands.push(current_commands);
         }
 
-        let x_min_size = cmp::min(500 - (y_size.1 - y_size.0 + 1), x_size.0 + 1);
-        let x_max_size = cmp::max(500 + (y_size.1 - y_size.0 + 1), x_size.1);
-
-        let cells = vec![
-            vec![CaveSliceElement::Air; x_max_size - x_min_size + 10];
-            y_size.1 - y_size.0 + 1
-        ];
+        // sand piles up to the floor in a triangle as wide as it is tall, which can reach
+        // past x = 0 when the cave is deep enough
+        let height = y_size.1 - y_size.0 + 1;
+        let x_min_size = cmp::min(500 - height as isize, x_size.0 as isize + 1);
+        let x_max_size = cmp::max(500 + height as isize, x_size.1 as isize);
 
-        let mut cave_slice = CaveSlice {
-            x_start: x_min_size - 5,
-            y_start: y_size.0,
-            cells,
-        };
-
-        cave_slice[(500, 0)] = CaveSliceElement::Source;
+        let mut cells = Grid::with_origin(
+            (x_min_size - 5, y_size.0 as isize),
+            (x_max_size - x_min_size) as usize + 10,
+            height,
+            CaveSliceElement::Air,
+        );
+        cells[(500, 0)] = CaveSliceElement::Source;
 
         for command in commands {
             for (start, finish) in command.into_iter().tuple_windows() {
@@ -156,73 +141,81 @@ impl FromStr for CaveSlice {
 
                 for x in x_range.0..=x_range.1 {
                     for y in y_range.0..=y_range.1 {
-                        cave_slice[(x, y)] = CaveSliceElement::Rock
+                        cells[(x as isize, y as isize)] = CaveSliceElement::Rock
                     }
                 }
             }
         }
 
-        Ok(cave_slice)
+        Ok(CaveSlice { cells })
+    }
+}
+
+// the cells are much easier to make sense of drawn out than listed
+impl Debug for CaveSlice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (x_start, y_start) = self.cells.origin();
+        writeln!(f, "CaveSlice from ({}, {}):", x_start, y_start)?;
+        write!(f, "{}", self)
     }
 }
 
 impl Display for CaveSlice {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_char('.')?;
-        for _ in 0..(self.cells[0].len()) {
-            f.write_char('-')?;
-        }
-        f.write_char('.')?;
-        f.write_char('\n')?;
-
-        for row in &self.cells {
-            f.write_char('|')?;
-            for cell in row {
-                f.write_char(match cell {
-                    CaveSliceElement::Sand => 'o',
-                    CaveSliceElement::Rock => '█',
-                    CaveSliceElement::Air => ' ',
-                    CaveSliceElement::Source => '+',
-                })?;
-            }
-            f.write_char('|')?;
-            f.write_char('\n')?;
-        }
-        f.write_char('\'')?;
-        for _ in 0..(self.cells[0].len()) {
-            f.write_char('-')?;
-        }
-        f.write_char('\'')?;
+        let border = "-".repeat(self.cells.width());
 
-        Ok(())
+        writeln!(f, ".{}.", border)?;
+        for row in self.cells.to_string().lines() {
+            writeln!(f, "|{}|", row)?;
+        }
+        write!(f, "'{}'", border)
     }
 }
 
 impl crate::runner::Day for Day14 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
-        let mut slice = input.parse::<CaveSlice>().unwrap();
+    type Parsed = CaveSlice;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(input.parse()?)
+    }
+
+    fn part_1(slice: &Self::Parsed) -> anyhow::Result<Answer> {
+        let mut slice = slice.clone();
         let mut sand_counts = 0;
         while slice.simulate_sand().is_some() {
             sand_counts += 1;
         }
 
-        Ok(sand_counts.to_string())
+        Ok(sand_counts.into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("672")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        let mut slice = input.parse::<CaveSlice>().unwrap();
+    fn part_2(slice: &Self::Parsed) -> anyhow::Result<Answer> {
+        let mut slice = slice.clone();
         slice.insert_floor();
         let mut sand_counts = 0;
         while slice.simulate_sand().is_some() {
             sand_counts += 1;
         }
 
-        Ok(sand_counts.to_string())
+        Ok(sand_counts.into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("26831")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn makes_room_for_sand_in_deep_caves() {
+        let slice = "498,600 -> 498,602".parse::<CaveSlice>().unwrap();
+
+        assert_eq!(slice.get((498, 601)), Some(&CaveSliceElement::Rock));
+        assert_eq!(slice.get((500 - 603, 0)), Some(&CaveSliceElement::Air));
+    }
+}
@@ -1,49 +1,64 @@
 use std::cmp;
 
-pub struct Day15;
-
-fn parse(input: &str) -> impl Iterator<Item = ((i64, i64), (i64, i64))> + '_ {
-    input.lines().map(|input| {
-        let input = input.as_bytes();
-        let mut parts = [0; 4];
-        let mut start_index = 12;
-        let mut end_index;
-
-        for part in &mut parts {
-            loop {
-                match input.get(start_index) {
-                    Some(b'0'..=b'9') => break,
-                    _ => start_index += 1,
-                }
-            }
+use crate::{
+    answer::Answer,
+    error::{expect_prefix, parse_number, ParseError},
+};
 
-            end_index = start_index;
-            while let Some(b'0'..=b'9') = input.get(end_index) {
-                end_index += 1;
-            }
+pub struct Day15;
 
-            *part = std::str::from_utf8(&input[start_index..end_index])
-                .unwrap()
-                .parse()
-                .unwrap();
+type Point = (i64, i64);
 
-            start_index = end_index;
+/// Parses lines like `Sensor at x=2, y=18: closest beacon is at x=-2, y=15`
+pub fn parse(input: &str) -> impl Iterator<Item = Result<(Point, Point), ParseError>> + '_ {
+    input.lines().enumerate().map(|(line_idx, line)| {
+        let rest = expect_prefix(line_idx, line, "Sensor at x=")?;
+        let mut parts = [0; 4];
+        let mut remaining = rest;
+
+        for (part, separator) in
+            parts
+                .iter_mut()
+                .zip([", y=", ": closest beacon is at x=", ", y=", ""])
+        {
+            let (number, rest) = if separator.is_empty() {
+                (remaining, "")
+            } else {
+                remaining.split_once(separator).ok_or_else(|| {
+                    ParseError::at(
+                        line_idx,
+                        line,
+                        remaining,
+                        format!("expected {:?}", separator),
+                    )
+                })?
+            };
+            *part = parse_number(line_idx, line, number)?;
+            remaining = rest;
         }
-        ((parts[0], parts[1]), (parts[2], parts[3]))
+
+        Ok(((parts[0], parts[1]), (parts[2], parts[3])))
     })
 }
 
-fn manhatten(point1: (i64, i64), point2: (i64, i64)) -> i64 {
+fn manhatten(point1: Point, point2: Point) -> i64 {
     (point1.0 - point2.0).abs() + (point1.1 - point2.1).abs()
 }
 
 impl crate::runner::Day for Day15 {
-    fn part_1(input: &str) -> anyhow::Result<String> {
+    type Parsed = Vec<(Point, Point)>;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {
+        Ok(parse(input).collect::<Result<_, _>>()?)
+    }
+
+    fn part_1(readings: &Self::Parsed) -> anyhow::Result<Answer> {
         const Y_LEVEL: i64 = 2000000; //10;
         let mut smallest = i64::MAX;
         let mut largest = i64::MIN;
-        let (mut sensors, beacons): (Vec<_>, Vec<_>) = parse(input)
-            .map(|(sensor, beacon)| ((sensor, manhatten(sensor, beacon)), beacon))
+        let (mut sensors, beacons): (Vec<_>, Vec<_>) = readings
+            .iter()
+            .map(|&(sensor, beacon)| ((sensor, manhatten(sensor, beacon)), beacon))
             .inspect(|((sensor, manhatten), _)| {
                 // we can definitely make this cleverer by reducing the manhatten distance down
                 // but let's save that for later if we need it
@@ -78,15 +93,16 @@ impl crate::runner::Day for Day15 {
             idx += 1;
         }
 
-        Ok(found_count.to_string())
+        Ok(found_count.into())
     }
     fn expected_value_part_1() -> Option<&'static str> {
         Some("5809294")
     }
 
-    fn part_2(input: &str) -> anyhow::Result<String> {
-        let (mut sensors, beacons): (Vec<_>, Vec<_>) = parse(input)
-            .map(|(sensor, beacon)| ((sensor, manhatten(sensor, beacon)), beacon))
+    fn part_2(readings: &Self::Parsed) -> anyhow::Result<Answer> {
+        let (mut sensors, beacons): (Vec<_>, Vec<_>) = readings
+            .iter()
+            .map(|&(sensor, beacon)| ((sensor, manhatten(sensor, beacon)), beacon))
             .unzip();
         sensors.sort_unstable_by_key(|(_, distance)| -distance);
 
@@ -119,7 +135,7 @@ impl crate::runner::Day for Day15 {
 
         let found_position = found_position.unwrap();
 
-        Ok((found_position.0 * 4_000_000 + found_position.1).to_string())
+        Ok((found_position.0 * 4_000_000 + found_position.1).into())
     }
     fn expected_value_part_2() -> Option<&'static str> {
         Some("10693731308112")
@@ -0,0 +1,116 @@
+use std::{fmt::Display, str::FromStr};
+
+/// An error found while parsing a puzzle input, pointing at the text that caused it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// The one-indexed line the error was found on
+    pub line: usize,
+    /// The one-indexed column (in characters) the offending text starts at
+    pub column: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// Creates an error from the zero-indexed position of the offending text
+    pub fn new(
+        line_idx: usize,
+        column_idx: usize,
+        text: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            line: line_idx + 1,
+            column: column_idx + 1,
+            text: text.into(),
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error for `part`, which should be a slice of `line`
+    ///
+    /// The column is worked out from where `part` sits within `line`; if it isn't a
+    /// slice of `line` then the error points at the start of the line.
+    pub fn at(line_idx: usize, line: &str, part: &str, message: impl Into<String>) -> Self {
+        let offset = (part.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+        let column_idx = match line.get(..offset) {
+            Some(before) if offset + part.len() <= line.len() => before.chars().count(),
+            _ => 0,
+        };
+
+        Self::new(line_idx, column_idx, part, message)
+    }
+
+    /// Creates an error pointing just past the end of the input, for things that are missing
+    pub fn at_end(input: &str, message: impl Into<String>) -> Self {
+        Self::new(input.lines().count(), 0, "", message)
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )?;
+        if !self.text.is_empty() {
+            write!(f, " (found {:?})", self.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses `part` (a slice of `line`) as a number, pointing at it if that fails
+pub fn parse_number<T: FromStr>(line_idx: usize, line: &str, part: &str) -> Result<T, ParseError> {
+    part.parse()
+        .map_err(|_| ParseError::at(line_idx, line, part, "expected a number"))
+}
+
+/// Strips `prefix` from the start of `line`, or points at whatever is there instead
+pub fn expect_prefix<'a>(
+    line_idx: usize,
+    line: &'a str,
+    prefix: &str,
+) -> Result<&'a str, ParseError> {
+    line.strip_prefix(prefix)
+        .ok_or_else(|| ParseError::at(line_idx, line, line, format!("expected {:?}", prefix)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn points_at_the_offending_part_of_the_line() {
+        let line = "move 3 from x to 5";
+        let part = line.split(' ').nth(3).unwrap();
+        let error = ParseError::at(4, line, part, "expected a number");
+
+        assert_eq!(error.line, 5);
+        assert_eq!(error.column, 13);
+        assert_eq!(
+            error.to_string(),
+            "line 5, column 13: expected a number (found \"x\")"
+        );
+    }
+
+    #[test]
+    fn falls_back_to_the_start_of_the_line() {
+        let error = ParseError::at(0, "abc", "unrelated", "oops");
+        assert_eq!(error.column, 1);
+    }
+
+    #[test]
+    fn parses_numbers_or_points_at_them() {
+        assert_eq!(parse_number::<u32>(0, "a-12", &"a-12"[2..]), Ok(12));
+        assert_eq!(
+            parse_number::<u32>(0, "a-1x", &"a-1x"[2..])
+                .unwrap_err()
+                .column,
+            3
+        );
+    }
+}
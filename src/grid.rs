@@ -0,0 +1,303 @@
+use std::{
+    fmt::Display,
+    ops::{Index, IndexMut},
+};
+
+use crate::error::ParseError;
+
+/// A position in a grid, as `(x, y)` with `y` increasing downwards
+pub type Point = (isize, isize);
+
+/// The four directions that share an edge with a cell: up, right, down and left
+pub const ORTHOGONAL: [Point; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// The eight directions that share an edge or a corner with a cell, clockwise from up
+pub const ALL_DIRECTIONS: [Point; 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// Moves `point` one step in `direction`
+pub fn step(point: Point, direction: Point) -> Point {
+    (point.0 + direction.0, point.1 + direction.1)
+}
+
+/// A rectangle of cells, stored row by row
+///
+/// The top left cell can be at any point, including negative ones, so that puzzles can
+/// keep using the coordinates they are given.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    origin: Point,
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Creates a grid with its top left cell at `(0, 0)`, with every cell set to `value`
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::with_origin((0, 0), width, height, value)
+    }
+
+    /// Creates a grid with its top left cell at `origin`, with every cell set to `value`
+    pub fn with_origin(origin: Point, width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            origin,
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses a map drawn with one character per cell, with its top left cell at `(0, 0)`
+    ///
+    /// `cell` turns each character into a cell, given where it is, or returns `None` if the
+    /// character isn't allowed, in which case the error says `expected <expected>`.
+    pub fn parse(
+        input: &str,
+        expected: &str,
+        mut cell: impl FnMut(Point, char) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+
+        for (y, line) in input.lines().enumerate() {
+            let row_width = line.chars().count();
+            let width = *width.get_or_insert(row_width);
+            if row_width != width {
+                return Err(ParseError::at(
+                    y,
+                    line,
+                    line,
+                    format!("expected {} cells to match the first row", width),
+                ));
+            }
+
+            for (x, (offset, c)) in line.char_indices().enumerate() {
+                let value = cell((x as isize, y as isize), c).ok_or_else(|| {
+                    ParseError::at(
+                        y,
+                        line,
+                        &line[offset..offset + c.len_utf8()],
+                        format!("expected {}", expected),
+                    )
+                })?;
+                cells.push(value);
+            }
+            height += 1;
+        }
+
+        match width {
+            Some(width) if width > 0 => Ok(Self {
+                origin: (0, 0),
+                width,
+                height,
+                cells,
+            }),
+            _ => Err(ParseError::at_end(input, "expected a map")),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The position of the top left cell
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    /// The position of the bottom right cell
+    pub fn end(&self) -> Point {
+        (
+            self.origin.0 + self.width as isize - 1,
+            self.origin.1 + self.height as isize - 1,
+        )
+    }
+
+    fn offset(&self, (x, y): Point) -> Option<usize> {
+        let x = usize::try_from(x - self.origin.0).ok()?;
+        let y = usize::try_from(y - self.origin.1).ok()?;
+        (x < self.width && y < self.height).then_some(y * self.width + x)
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        self.offset(point).is_some()
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.offset(point).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.offset(point).map(|offset| &mut self.cells[offset])
+    }
+
+    /// Every point in the grid, row by row
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let (origin, width) = (self.origin, self.width as isize);
+        (0..(self.width * self.height) as isize)
+            .map(move |idx| (origin.0 + idx % width, origin.1 + idx / width))
+    }
+
+    /// Every cell in the grid along with its position, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// The points next to `point` in each of `directions` that are inside the grid
+    pub fn neighbours<'a>(
+        &'a self,
+        point: Point,
+        directions: &'a [Point],
+    ) -> impl Iterator<Item = Point> + 'a {
+        directions
+            .iter()
+            .map(move |&direction| step(point, direction))
+            .filter(|&neighbour| self.contains(neighbour))
+    }
+
+    /// The points that share an edge with `point` and are inside the grid
+    pub fn neighbours4(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &ORTHOGONAL)
+    }
+
+    /// The points that share an edge or a corner with `point` and are inside the grid
+    pub fn neighbours8(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        self.neighbours(point, &ALL_DIRECTIONS)
+    }
+
+    /// The points reached by repeatedly stepping from `start` in `direction`, until
+    /// leaving the grid. `start` itself isn't included.
+    pub fn ray(&self, start: Point, direction: Point) -> impl Iterator<Item = Point> + '_ {
+        std::iter::successors(Some(step(start, direction)), move |&point| {
+            Some(step(point, direction))
+        })
+        .take_while(|&point| self.contains(point))
+    }
+
+    /// Creates a grid of the same size and position, with each cell worked out from the
+    /// cell in the same place in this one
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            origin: self.origin,
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Adds a row to the bottom of the grid, with every cell set to `value`
+    pub fn push_row(&mut self, value: T)
+    where
+        T: Clone,
+    {
+        self.cells.extend(std::iter::repeat_n(value, self.width));
+        self.height += 1;
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        match self.offset(point) {
+            Some(offset) => &self.cells[offset],
+            None => panic!("{:?} is outside the grid", point),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        match self.offset(point) {
+            Some(offset) => &mut self.cells[offset],
+            None => panic!("{:?} is outside the grid", point),
+        }
+    }
+}
+
+/// Draws the grid with each cell's `Display` output, one row per line
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for (idx, row) in self.rows().enumerate() {
+            if idx > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn indexes_from_its_origin() {
+        let mut grid = Grid::with_origin((-2, 3), 3, 2, 0);
+        grid[(-2, 3)] = 1;
+        grid[(0, 4)] = 2;
+
+        assert_eq!(grid.get((-2, 3)), Some(&1));
+        assert_eq!(grid.get((-3, 3)), None);
+        assert_eq!(grid.get((1, 4)), None);
+        assert_eq!(grid.end(), (0, 4));
+        assert_eq!(grid.to_string(), "100\n002");
+        assert_eq!(grid.points().last(), Some((0, 4)));
+    }
+
+    #[test]
+    fn finds_neighbours_inside_the_grid() {
+        let grid = Grid::new(3, 3, ());
+
+        assert_eq!(grid.neighbours4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours8((0, 1)).count(), 5);
+        assert_eq!(grid.neighbours8((1, 1)).count(), 8);
+        assert_eq!(
+            grid.ray((0, 1), (1, 0)).collect::<Vec<_>>(),
+            vec![(1, 1), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn parses_character_maps() {
+        let grid = Grid::parse("12\n34\n", "a digit", |_, c| c.to_digit(10)).unwrap();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(grid[(0, 1)], 3);
+
+        let error = Grid::parse("12\n3x", "a digit", |_, c| c.to_digit(10)).unwrap_err();
+        assert_eq!((error.line, error.column), (2, 2));
+        assert_eq!(error.message, "expected a digit");
+
+        let error = Grid::parse("12\n345", "a digit", |_, c| c.to_digit(10)).unwrap_err();
+        assert_eq!(error.message, "expected 2 cells to match the first row");
+        assert!(Grid::parse("", "a digit", |_, c| c.to_digit(10)).is_err());
+    }
+}
@@ -0,0 +1,438 @@
+use std::{
+    collections::HashMap,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    process::Command,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::report::{DayReport, Status};
+
+/// The result of running one part of one day, as stored in the results log
+///
+/// The log is a file of JSON lines, one record per part, appended to by `--record`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Record {
+    /// When the run this came from was recorded, in milliseconds since the Unix epoch
+    pub run: u64,
+    pub commit: Option<String>,
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    /// The FNV-1a hash of the input, so that runs against different inputs aren't compared
+    pub input_hash: String,
+    pub status: Status,
+    pub answer: Option<String>,
+    /// The raw pixels of a grid answer, which `answer` shows as letters when it can
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub drawing: Option<String>,
+    /// How long the part took, which is the median of the `--bench` runs if there were any
+    pub duration_secs: f64,
+    /// Whether `duration_secs` is a `--bench` median rather than a single run
+    #[serde(default)]
+    pub benched: bool,
+}
+
+impl Record {
+    fn key(&self) -> (u8, u8, &str) {
+        (self.day, self.part, &self.input_hash)
+    }
+
+    fn duration(&self) -> Duration {
+        Duration::from_secs_f64(self.duration_secs)
+    }
+}
+
+/// Slowdowns smaller than this are ignored however large they are as a percentage, as
+/// they are lost in the noise of timing a single run
+const MIN_SLOWDOWN_SECS: f64 = 0.001;
+
+/// Something notable about how a part changed between two runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    AnswerChanged,
+    /// The part got slower by more than the threshold, by this percentage
+    Slower(f64),
+}
+
+impl Change {
+    pub fn between(before: &Record, after: &Record, threshold: f64) -> Vec<Self> {
+        let mut changes = Vec::new();
+        if before.answer != after.answer {
+            changes.push(Self::AnswerChanged);
+        }
+
+        let percent = (after.duration_secs / before.duration_secs - 1.0) * 100.0;
+        let slowdown = after.duration_secs - before.duration_secs;
+        if before.duration_secs > 0.0 && percent > threshold && slowdown > MIN_SLOWDOWN_SECS {
+            changes.push(Self::Slower(percent));
+        }
+
+        changes
+    }
+}
+
+pub fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
+/// The commit checked out in `dir`, if it is in a git repository
+pub fn git_commit(dir: &Path) -> Option<String> {
+    let output = Command::new("git")
+        .args(["rev-parse", "HEAD"])
+        .current_dir(dir)
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(String::from_utf8(output.stdout).ok()?.trim().to_owned())
+}
+
+/// Builds the records for a run, skipping any inputs that couldn't be read
+pub fn records(reports: &[DayReport], commit: Option<String>) -> Vec<Record> {
+    let run = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis() as u64;
+
+    reports
+        .iter()
+        .filter_map(|report| Some((report, report.input_hash?)))
+        .flat_map(|(report, hash)| {
+            let commit = &commit;
+            report.parts.iter().map(move |part| Record {
+                run,
+                commit: commit.clone(),
+                day: report.day,
+                part: part.part,
+                input: report.input.to_string_lossy().into_owned(),
+                input_hash: format!("{:016x}", hash),
+                status: part.status,
+                answer: part.answer.clone(),
+                drawing: part.drawing.clone(),
+                duration_secs: part
+                    .bench
+                    .map_or(part.duration, |bench| bench.median)
+                    .as_secs_f64(),
+                benched: part.bench.is_some(),
+            })
+        })
+        .collect()
+}
+
+pub fn append(path: &Path, records: &[Record]) -> anyhow::Result<()> {
+    let mut lines = String::new();
+    for record in records {
+        lines.push_str(&serde_json::to_string(record)?);
+        lines.push('\n');
+    }
+
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(lines.as_bytes()))
+        .with_context(|| format!("could not write to {:?}", path))
+}
+
+/// Loads every record in the results log at `path`, treating a missing file as empty
+pub fn load(path: &Path) -> anyhow::Result<Vec<Record>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err).with_context(|| format!("could not read {:?}", path)),
+    };
+
+    contents
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str(line)
+                .with_context(|| format!("could not parse line {} of {:?}", idx + 1, path))
+        })
+        .collect()
+}
+
+/// Splits the log into the records of each run, oldest first
+pub fn runs(records: &[Record]) -> Vec<&[Record]> {
+    records.chunk_by(|a, b| a.run == b.run).collect()
+}
+
+/// Shortens a commit hash, or marks it as unknown
+fn short_commit(commit: &Option<String>) -> &str {
+    match commit {
+        Some(commit) => commit.get(..8).unwrap_or(commit),
+        None => "unknown",
+    }
+}
+
+/// Shows the first line of an answer, so that multi-line answers fit in a table
+fn one_line(answer: &Option<String>) -> String {
+    let Some(answer) = answer else {
+        return "-".to_owned();
+    };
+
+    let mut lines = answer.lines();
+    let first = lines.next().unwrap_or_default();
+    if lines.next().is_some() {
+        format!("{}...", first)
+    } else {
+        first.to_owned()
+    }
+}
+
+/// Describes a set of changes, with a leading space if there are any
+fn describe(changes: &[Change]) -> String {
+    changes
+        .iter()
+        .map(|change| match change {
+            Change::AnswerChanged => " !ANSWER CHANGED!".to_owned(),
+            Change::Slower(percent) => format!(" !{:.0}% SLOWER!", percent),
+        })
+        .collect()
+}
+
+/// Writes every recorded result for each part, flagging where it changed from the run before
+pub fn write_history(
+    out: &mut impl Write,
+    records: &[Record],
+    days: &[u8],
+    threshold: f64,
+) -> io::Result<()> {
+    let runs = runs(records);
+    let mut by_part: HashMap<_, Vec<(usize, &Record)>> = HashMap::new();
+    for (idx, run) in runs.iter().enumerate() {
+        for record in run.iter() {
+            if days.is_empty() || days.contains(&record.day) {
+                by_part
+                    .entry(record.key())
+                    .or_default()
+                    .push((idx + 1, record));
+            }
+        }
+    }
+
+    let mut keys = by_part.keys().copied().collect::<Vec<_>>();
+    keys.sort_unstable();
+
+    for key in keys {
+        let history = &by_part[&key];
+        let (_, first) = history[0];
+        writeln!(
+            out,
+            "Day {} part {}, input {} ({})",
+            first.day, first.part, first.input, first.input_hash
+        )?;
+        writeln!(out, "  run | commit   | {:<12} | answer", "duration")?;
+
+        let mut previous: Option<&Record> = None;
+        for &(run, record) in history {
+            let changes = match previous {
+                Some(previous) => Change::between(previous, record, threshold),
+                None => Vec::new(),
+            };
+            writeln!(
+                out,
+                "  {:>3} | {:<8} | {:<12} | {}{}",
+                run,
+                short_commit(&record.commit),
+                format!("{:?}", record.duration()),
+                one_line(&record.answer),
+                describe(&changes),
+            )?;
+            previous = Some(record);
+        }
+        writeln!(out)?;
+    }
+
+    Ok(())
+}
+
+/// Compares the results of two runs (numbered from 1, oldest first)
+///
+/// Returns true if no answers changed and nothing got slower than the threshold.
+pub fn write_comparison(
+    out: &mut impl Write,
+    records: &[Record],
+    from: usize,
+    to: usize,
+    threshold: f64,
+) -> anyhow::Result<bool> {
+    let runs = runs(records);
+    let run = |number: usize| {
+        number
+            .checked_sub(1)
+            .and_then(|idx| runs.get(idx))
+            .with_context(|| format!("there is no run {} ({} runs recorded)", number, runs.len()))
+    };
+    let (before, after) = (run(from)?, run(to)?);
+    if !after
+        .iter()
+        .any(|new| before.iter().any(|old| old.key() == new.key()))
+    {
+        anyhow::bail!(
+            "runs {} and {} have no parts in common, as none share a day, part and input",
+            from,
+            to
+        );
+    }
+
+    writeln!(
+        out,
+        "Comparing run {} ({}) with run {} ({})",
+        from,
+        short_commit(&before[0].commit),
+        to,
+        short_commit(&after[0].commit)
+    )?;
+    writeln!(
+        out,
+        "  day | part | {:<20} | {:<12} | {:<12} | change",
+        "input", "before", "after"
+    )?;
+
+    let mut compared = 0;
+    let mut regressions = 0;
+    for new in after.iter() {
+        let Some(old) = before.iter().find(|old| old.key() == new.key()) else {
+            continue;
+        };
+        compared += 1;
+
+        let changes = Change::between(old, new, threshold);
+        if !changes.is_empty() {
+            regressions += 1;
+        }
+
+        let percent = (new.duration_secs / old.duration_secs - 1.0) * 100.0;
+        writeln!(
+            out,
+            "  {:>3} | {:>4} | {:<20} | {:<12} | {:<12} | {:+.0}%{}",
+            new.day,
+            new.part,
+            Path::new(&new.input)
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy(),
+            format!("{:?}", old.duration()),
+            format!("{:?}", new.duration()),
+            percent,
+            describe(&changes),
+        )?;
+        if changes.contains(&Change::AnswerChanged) {
+            writeln!(
+                out,
+                "             {} -> {}",
+                one_line(&old.answer),
+                one_line(&new.answer)
+            )?;
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{} parts compared, {} changed answers or got more than {}% slower",
+        compared, regressions, threshold
+    )?;
+    if before
+        .iter()
+        .chain(after.iter())
+        .any(|record| !record.benched)
+    {
+        writeln!(
+            out,
+            "Some timings are from a single run; record with --bench for steadier comparisons"
+        )?;
+    }
+
+    Ok(regressions == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(run: u64, answer: &str, duration_secs: f64) -> Record {
+        Record {
+            run,
+            commit: None,
+            day: 1,
+            part: 1,
+            input: "inputs/day_1.txt".to_owned(),
+            input_hash: format!("{:016x}", fnv1a(b"1000")),
+            status: Status::Unchecked,
+            answer: Some(answer.to_owned()),
+            drawing: None,
+            duration_secs,
+            benched: false,
+        }
+    }
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn flags_changed_answers_and_slowdowns() {
+        let before = record(1, "24000", 1.0);
+        assert_eq!(
+            Change::between(&before, &record(2, "24000", 1.05), 10.0),
+            vec![]
+        );
+        assert_eq!(
+            Change::between(&before, &record(2, "45000", 0.5), 10.0),
+            vec![Change::AnswerChanged]
+        );
+        assert_eq!(
+            Change::between(&before, &record(2, "24000", 1.5), 10.0),
+            vec![Change::Slower(50.0)]
+        );
+        assert_eq!(
+            Change::between(
+                &record(1, "24000", 0.0002),
+                &record(2, "24000", 0.0006),
+                10.0
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn refuses_to_compare_runs_with_nothing_in_common() {
+        let mut demo = record(2, "24000", 1.0);
+        demo.input_hash = format!("{:016x}", fnv1a(b"demo"));
+        let records = [record(1, "24000", 1.0), demo];
+
+        let error = write_comparison(&mut Vec::new(), &records, 1, 2, 10.0).unwrap_err();
+        assert!(error.to_string().contains("no parts in common"));
+        assert!(write_comparison(&mut Vec::new(), &records, 1, 1, 10.0).unwrap());
+    }
+
+    #[test]
+    fn groups_records_into_runs() {
+        let records = [
+            record(1, "a", 1.0),
+            record(1, "b", 1.0),
+            record(2, "c", 1.0),
+        ];
+        let runs = runs(&records);
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].len(), 2);
+    }
+}
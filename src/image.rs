@@ -0,0 +1,68 @@
+use std::io::{self, Write};
+
+use crate::grid::Grid;
+
+/// A colour, as red, green and blue
+pub type Rgb = [u8; 3];
+
+/// Blends from `from` to `to`, where `amount` goes from 0 (all `from`) to 1 (all `to`)
+pub fn blend(from: Rgb, to: Rgb, amount: f64) -> Rgb {
+    let amount = amount.clamp(0.0, 1.0);
+    [0, 1, 2].map(|idx| {
+        let (from, to) = (from[idx] as f64, to[idx] as f64);
+        (from + (to - from) * amount).round() as u8
+    })
+}
+
+/// Writes a grid of colours as a binary PPM image, drawing each cell as a `scale` by
+/// `scale` square of pixels
+///
+/// PPM is about the simplest image format there is, and most image viewers can open it.
+pub fn write_ppm(out: &mut impl Write, pixels: &Grid<Rgb>, scale: usize) -> io::Result<()> {
+    write!(
+        out,
+        "P6\n{} {}\n255\n",
+        pixels.width() * scale,
+        pixels.height() * scale
+    )?;
+
+    let mut line = Vec::with_capacity(pixels.width() * scale * 3);
+    for row in pixels.rows() {
+        line.clear();
+        for colour in row {
+            for _ in 0..scale {
+                line.extend_from_slice(colour);
+            }
+        }
+        for _ in 0..scale {
+            out.write_all(&line)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn writes_scaled_ppm_images() {
+        let mut pixels = Grid::new(2, 1, [0, 0, 0]);
+        pixels[(1, 0)] = [255, 128, 0];
+
+        let mut out = Vec::new();
+        write_ppm(&mut out, &pixels, 2).unwrap();
+
+        let header = b"P6\n4 2\n255\n";
+        assert_eq!(&out[..header.len()], header);
+        let row = [0, 0, 0, 0, 0, 0, 255, 128, 0, 255, 128, 0];
+        assert_eq!(&out[header.len()..], [row, row].concat());
+    }
+
+    #[test]
+    fn blends_between_colours() {
+        assert_eq!(blend([0, 0, 0], [200, 100, 50], 0.5), [100, 50, 25]);
+        assert_eq!(blend([0, 0, 0], [200, 100, 50], 2.0), [200, 100, 50]);
+    }
+}
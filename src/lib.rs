@@ -0,0 +1,48 @@
+//! Solutions for Advent of Code 2022, along with the runner used to execute and check them
+//!
+//! Each day's solution lives in its own module (e.g. [`day15::Day15`]) and implements the
+//! [`Day`] trait, so it can be called directly:
+//!
+//! ```no_run
+//! use aoc_2022::{day15::Day15, Day};
+//!
+//! let input = std::fs::read_to_string("inputs/day_15.txt").unwrap();
+//! println!("{}", Day15::part_2(&Day15::parse(&input).unwrap()).unwrap());
+//! ```
+
+pub mod answer;
+pub mod answers;
+pub mod error;
+pub mod grid;
+pub mod history;
+pub mod image;
+pub mod ocr;
+pub mod panics;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod visualise;
+pub mod watch;
+pub mod worker;
+
+pub use runner::Day;
+
+registry::days! {
+    1 => day01::Day1, "Calorie Counting";
+    2 => day02::Day2, "Rock Paper Scissors";
+    3 => day03::Day3, "Rucksack Reorganization";
+    4 => day04::Day4, "Camp Cleanup";
+    5 => day05::Day5, "Supply Stacks";
+    6 => day06::Day6, "Tuning Trouble";
+    7 => day07::Day7, "No Space Left On Device";
+    8 => day08::Day8, "Treetop Tree House";
+    9 => day09::Day9, "Rope Bridge";
+    10 => day10::Day10, "Cathode-Ray Tube";
+    11 => day11::Day11, "Monkey in the Middle";
+    12 => day12::Day12, "Hill Climbing Algorithm";
+    13 => day13::Day13, "Distress Signal";
+    14 => day14::Day14, "Regolith Reservoir";
+    15 => day15::Day15, "Beacon Exclusion Zone";
+}
@@ -1,23 +1,9 @@
-use clap::Parser;
+use std::process::ExitCode;
 
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod runner;
+use aoc_2022::runner::AdventOfCodeRunner;
+use clap::Parser;
 
-fn main() {
-    let runner = runner::AdventOfCodeRunner::parse();
-    runner.run().unwrap();
+fn main() -> anyhow::Result<ExitCode> {
+    let runner = AdventOfCodeRunner::parse();
+    runner.run()
 }
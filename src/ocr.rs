@@ -0,0 +1,111 @@
+use crate::answer::PixelGrid;
+
+const GLYPH_WIDTH: usize = 4;
+const GLYPH_HEIGHT: usize = 6;
+/// Each glyph is followed by a blank column before the next one starts
+const GLYPH_SPACING: usize = GLYPH_WIDTH + 1;
+
+/// The letters of the font puzzles draw their answers in, as far as they are known
+const GLYPHS: &[(char, [&str; GLYPH_HEIGHT])] = &[
+    ('A', [".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', [".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', ["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', [".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('J', ["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', ["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Z', ["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+/// Reads the letters drawn in a grid, in the 4x6 font that puzzles draw their answers in
+///
+/// Returns `None` if the grid isn't the right shape for a row of letters, or if any of
+/// the glyphs in it aren't known.
+pub fn read_letters(grid: &PixelGrid) -> Option<String> {
+    // the blank column after the last letter is sometimes left off
+    if grid.height() != GLYPH_HEIGHT || (grid.width() + 1) % GLYPH_SPACING > 1 {
+        return None;
+    }
+
+    (0..grid.width().div_ceil(GLYPH_SPACING))
+        .map(|idx| {
+            let left = idx * GLYPH_SPACING;
+            if (0..GLYPH_HEIGHT).any(|y| grid.get(left + GLYPH_WIDTH, y)) {
+                return None;
+            }
+
+            GLYPHS
+                .iter()
+                .find(|(_, rows)| {
+                    rows.iter().enumerate().all(|(y, row)| {
+                        row.bytes()
+                            .enumerate()
+                            .all(|(x, pixel)| grid.get(left + x, y) == (pixel == b'#'))
+                    })
+                })
+                .map(|&(letter, _)| letter)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::answer::Answer;
+
+    const SCREEN: &str = "
+        ####..##..#.....##..#..#.#....###...##..
+        #....#..#.#....#..#.#..#.#....#..#.#..#.
+        ###..#..#.#....#....#..#.#....#..#.#....
+        #....####.#....#.##.#..#.#....###..#.##.
+        #....#..#.#....#..#.#..#.#....#....#..#.
+        ####.#..#.####..###..##..####.#.....###.
+    ";
+
+    #[test]
+    fn reads_letters_from_a_screen() {
+        let grid = SCREEN.parse().unwrap();
+        assert_eq!(read_letters(&grid), Some("EALGULPG".to_owned()));
+
+        let answer = Answer::Grid(grid);
+        assert_eq!(answer.to_string(), "EALGULPG");
+        assert_eq!(format!("{:#}", answer), SCREEN.trim().replace(' ', ""));
+        assert!(answer.matches("EALGULPG\n"));
+        assert!(answer.matches(SCREEN));
+        assert!(!answer.matches("EALGULPC"));
+    }
+
+    #[test]
+    fn reads_every_known_glyph() {
+        for (letter, rows) in GLYPHS {
+            let grid = rows.join("\n").parse().unwrap();
+            assert_eq!(read_letters(&grid), Some(letter.to_string()));
+        }
+    }
+
+    #[test]
+    fn falls_back_to_the_grid_for_unknown_glyphs() {
+        let grid: PixelGrid = "
+            ##..##..##..##..##..##..##..##..##..##..
+            ###...###...###...###...###...###...###.
+            ####....####....####....####....####....
+            #####.....#####.....#####.....#####.....
+            ######......######......######......####
+            #######.......#######.......#######.....
+        "
+        .parse()
+        .unwrap();
+
+        assert_eq!(read_letters(&grid), None);
+        assert_eq!(Answer::Grid(grid.clone()).to_string(), grid.to_string());
+        assert_eq!(read_letters(&"#...\n#...".parse().unwrap()), None);
+    }
+}
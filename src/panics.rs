@@ -0,0 +1,107 @@
+use std::{
+    any::Any,
+    cell::{Cell, RefCell},
+    fmt::Display,
+    panic::{self, AssertUnwindSafe},
+    sync::Once,
+};
+
+use serde::{Deserialize, Serialize};
+
+thread_local! {
+    /// Whether panics on this thread are being caught by `catch`, and so shouldn't be printed
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+    /// Where the most recent caught panic on this thread happened
+    static LOCATION: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+/// A panic caught while running a solution
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Panic {
+    pub message: String,
+    pub location: Option<String>,
+}
+
+impl Panic {
+    /// Whether this came from `unimplemented!` or `todo!`, rather than a real crash
+    pub fn is_unimplemented(&self) -> bool {
+        self.message.starts_with("not implemented")
+            || self.message.starts_with("not yet implemented")
+    }
+
+    fn from_payload(payload: Box<dyn Any + Send>, location: Option<String>) -> Self {
+        let message = match payload.downcast::<String>() {
+            Ok(message) => *message,
+            Err(payload) => match payload.downcast::<&'static str>() {
+                Ok(message) => (*message).to_owned(),
+                Err(_) => "Box<dyn Any>".to_owned(),
+            },
+        };
+
+        Self { message, location }
+    }
+}
+
+impl Display for Panic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.location {
+            Some(location) => write!(f, "panicked at {}: {}", location, self.message),
+            None => write!(f, "panicked: {}", self.message),
+        }
+    }
+}
+
+/// Installs a panic hook that stays quiet for panics caught by `catch`, and otherwise
+/// defers to whatever hook was there before
+fn install_hook() {
+    INSTALL_HOOK.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if CATCHING.with(Cell::get) {
+                let location = info.location().map(|l| l.to_string());
+                LOCATION.with(|cell| *cell.borrow_mut() = location);
+            } else {
+                previous(info);
+            }
+        }));
+    });
+}
+
+/// Runs `f`, turning any panic inside it into an error instead of unwinding further
+pub fn catch<T>(f: impl FnOnce() -> T) -> Result<T, Panic> {
+    install_hook();
+
+    let was_catching = CATCHING.with(|cell| cell.replace(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    CATCHING.with(|cell| cell.set(was_catching));
+
+    result.map_err(|payload| {
+        let location = LOCATION.with(|cell| cell.borrow_mut().take());
+        Panic::from_payload(payload, location)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn catches_panics_with_their_message_and_location() {
+        assert_eq!(catch(|| 5), Ok(5));
+
+        let panic = catch(|| panic!("Path not found!")).unwrap_err();
+        assert_eq!(panic.message, "Path not found!");
+        assert!(panic.location.unwrap().starts_with("src/panics.rs:"));
+        assert!(!catch(|| panic!("{}", 5)).unwrap_err().is_unimplemented());
+    }
+
+    #[test]
+    fn recognises_unimplemented_parts() {
+        assert!(catch(|| unimplemented!("part 1"))
+            .unwrap_err()
+            .is_unimplemented());
+        assert!(catch(|| todo!()).unwrap_err().is_unimplemented());
+    }
+}
@@ -0,0 +1,105 @@
+use std::{any::Any, fmt::Debug};
+
+use crate::answer::Answer;
+use crate::runner::{Day, Example};
+
+/// The parsed input of some day, with its type erased so that every day can be handled alike
+pub trait ParsedInput: Any + Debug + Send + Sync {}
+
+impl<T: Any + Debug + Send + Sync> ParsedInput for T {}
+
+/// A part of some day, taking the input parsed by that same day
+pub type Part = fn(&dyn ParsedInput) -> anyhow::Result<Answer>;
+
+/// A type-erased handle on a `Day` implementation, along with some metadata about it
+pub struct DayEntry {
+    pub day: u8,
+    pub title: &'static str,
+    pub parse: fn(&str) -> anyhow::Result<Box<dyn ParsedInput>>,
+    pub part_1: Part,
+    pub part_2: Part,
+    /// Whether each part has been implemented, rather than left to panic
+    pub implemented: (bool, bool),
+    pub expected_value_part_1: fn() -> Option<&'static str>,
+    pub expected_value_part_2: fn() -> Option<&'static str>,
+    pub examples: fn() -> &'static [Example],
+}
+
+impl DayEntry {
+    pub const fn new<D: Day>(day: u8, title: &'static str) -> Self {
+        Self {
+            day,
+            title,
+            parse: parse::<D>,
+            part_1: part_1::<D>,
+            part_2: part_2::<D>,
+            implemented: (D::PART_1_IMPLEMENTED, D::PART_2_IMPLEMENTED),
+            expected_value_part_1: <D as Day>::expected_value_part_1,
+            expected_value_part_2: <D as Day>::expected_value_part_2,
+            examples: <D as Day>::examples,
+        }
+    }
+
+    pub fn part(&self, part: u8) -> Part {
+        match part {
+            1 => self.part_1,
+            _ => self.part_2,
+        }
+    }
+
+    /// Parses the input and runs a part on it, for when the parsed input won't be reused
+    pub fn solve(&self, part: u8, input: &str) -> anyhow::Result<Answer> {
+        self.part(part)(&*(self.parse)(input)?)
+    }
+}
+
+fn parse<D: Day>(input: &str) -> anyhow::Result<Box<dyn ParsedInput>> {
+    Ok(Box::new(D::parse(input)?))
+}
+
+fn downcast<D: Day>(input: &dyn ParsedInput) -> &D::Parsed {
+    (input as &dyn Any)
+        .downcast_ref()
+        .expect("a part was given the input parsed by a different day")
+}
+
+fn part_1<D: Day>(input: &dyn ParsedInput) -> anyhow::Result<Answer> {
+    D::part_1(downcast::<D>(input))
+}
+
+fn part_2<D: Day>(input: &dyn ParsedInput) -> anyhow::Result<Answer> {
+    D::part_2(downcast::<D>(input))
+}
+
+/// Finds the entry for a given day in a registry
+pub fn find(days: &'static [DayEntry], day: u8) -> Option<&'static DayEntry> {
+    days.iter().find(|entry| entry.day == day)
+}
+
+/// Declares the module for each day, and builds the `DAYS` registry from them
+///
+/// Each line has the form `<day> => <module>::<type>, "<title>";`. A test is also
+/// generated for each day that checks it against its expected answers.
+macro_rules! days {
+    ($($day:literal => $module:ident :: $day_type:ident, $title:literal;)*) => {
+        $(pub mod $module;)*
+
+        pub const DAYS: &[$crate::registry::DayEntry] = &[
+            $($crate::registry::DayEntry::new::<$module::$day_type>($day, $title),)*
+        ];
+
+        #[cfg(test)]
+        mod expectation_tests {
+            $(
+                #[test]
+                fn $module() {
+                    $crate::runner::assert_expected_answers(
+                        $crate::registry::find(super::DAYS, $day).unwrap(),
+                    );
+                }
+            )*
+        }
+    };
+}
+
+pub(crate) use days;
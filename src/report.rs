@@ -0,0 +1,381 @@
+use std::{io::Write, path::PathBuf, time::Duration};
+
+use serde::{Deserialize, Serialize, Serializer};
+
+use crate::answer::Answer;
+
+#[derive(clap::ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,
+    Json,
+    Csv,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Pass,
+    Fail,
+    Unchecked,
+    Error,
+    /// The part panicked
+    Crashed,
+    /// The part hit `unimplemented!` or `todo!`
+    NotImplemented,
+    /// The part was killed for running longer than `--timeout`
+    TimedOut,
+}
+
+impl Status {
+    pub fn check(answer: &Answer, expected: Option<&str>) -> Self {
+        match expected {
+            None => Self::Unchecked,
+            Some(expected) if answer.matches(expected) => Self::Pass,
+            Some(_) => Self::Fail,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Pass => "pass",
+            Self::Fail => "fail",
+            Self::Unchecked => "unchecked",
+            Self::Error => "error",
+            Self::Crashed => "crashed",
+            Self::NotImplemented => "not_implemented",
+            Self::TimedOut => "timed_out",
+        }
+    }
+}
+
+/// Summary statistics over a set of timing samples
+#[derive(Serialize, Debug, Clone, Copy)]
+pub struct Timings {
+    #[serde(rename = "min_secs", serialize_with = "as_secs")]
+    pub min: Duration,
+    #[serde(rename = "median_secs", serialize_with = "as_secs")]
+    pub median: Duration,
+    #[serde(rename = "mean_secs", serialize_with = "as_secs")]
+    pub mean: Duration,
+    #[serde(rename = "max_secs", serialize_with = "as_secs")]
+    pub max: Duration,
+}
+
+impl Timings {
+    pub fn from_samples(mut samples: Vec<Duration>) -> Self {
+        samples.sort_unstable();
+
+        let len = samples.len();
+        let median = if len.is_multiple_of(2) {
+            (samples[len / 2 - 1] + samples[len / 2]) / 2
+        } else {
+            samples[len / 2]
+        };
+
+        Self {
+            min: samples[0],
+            median,
+            mean: samples.iter().sum::<Duration>() / len as u32,
+            max: samples[len - 1],
+        }
+    }
+}
+
+#[derive(Serialize, Debug)]
+pub struct PartReport {
+    pub part: u8,
+    pub answer: Option<String>,
+    /// The raw pixels of a grid answer, which `answer` shows as letters when it can
+    pub drawing: Option<String>,
+    pub expected: Option<String>,
+    pub status: Status,
+    pub error: Option<String>,
+    #[serde(rename = "duration_secs", serialize_with = "as_secs")]
+    pub duration: Duration,
+    pub bench: Option<Timings>,
+}
+
+#[derive(Serialize, Debug)]
+pub struct DayReport {
+    pub day: u8,
+    pub input: PathBuf,
+    #[serde(rename = "load_time_secs", serialize_with = "as_secs")]
+    pub load_time: Duration,
+    /// How long the input took to parse, if it could be read
+    #[serde(rename = "parse_time_secs", serialize_with = "as_optional_secs")]
+    pub parse_time: Option<Duration>,
+    /// Timings of every `--bench` parse, if the input could be parsed
+    pub parse_bench: Option<Timings>,
+    /// The FNV-1a hash of the input, if it could be read
+    #[serde(skip)]
+    pub input_hash: Option<u64>,
+    pub parts: Vec<PartReport>,
+}
+
+fn as_secs<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64())
+}
+
+fn as_optional_secs<S: Serializer>(
+    duration: &Option<Duration>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match duration {
+        Some(duration) => serializer.serialize_some(&duration.as_secs_f64()),
+        None => serializer.serialize_none(),
+    }
+}
+
+pub fn write_text(out: &mut impl Write, report: &DayReport) -> std::io::Result<()> {
+    writeln!(out, "Executing day {}", report.day)?;
+    write!(
+        out,
+        "  [using input {:?}, loaded in {:?}",
+        report.input, report.load_time
+    )?;
+    if let Some(parse_time) = report.parse_time {
+        write!(out, ", parsed in {:?}", parse_time)?;
+    }
+    writeln!(out, "]")?;
+    if let Some(timings) = report.parse_bench {
+        writeln!(
+            out,
+            "  [parse bench: min {:?}, median {:?}, mean {:?}, max {:?}]",
+            timings.min, timings.median, timings.mean, timings.max
+        )?;
+    }
+    writeln!(out)?;
+
+    for part in &report.parts {
+        writeln!(out, "Executing part {}", part.part)?;
+        if let Some(answer) = &part.answer {
+            writeln!(out, "  {}", answer)?;
+        }
+        if let Some(drawing) = part
+            .drawing
+            .as_ref()
+            .filter(|&d| Some(d) != part.answer.as_ref())
+        {
+            for row in drawing.lines() {
+                writeln!(out, "  {}", row)?;
+            }
+        }
+        match (part.status, &part.error) {
+            (Status::NotImplemented, _) => writeln!(out, "  [not implemented]")?,
+            (Status::Crashed, Some(error)) => writeln!(out, "  !CRASHED! {}", error)?,
+            (Status::TimedOut, Some(error)) => writeln!(out, "  !TIMED OUT! {}", error)?,
+            (_, Some(error)) => writeln!(out, "  !ERROR! {}", error)?,
+            (_, None) => {}
+        }
+        if let Some(expected) = &part.expected {
+            writeln!(
+                out,
+                "  [expected = {}{}]",
+                expected,
+                if part.status == Status::Fail {
+                    " !FAILED!"
+                } else {
+                    ""
+                }
+            )?;
+        }
+        writeln!(out, "  [took {:?}]", part.duration)?;
+        if let Some(timings) = part.bench {
+            writeln!(
+                out,
+                "  [bench: min {:?}, median {:?}, mean {:?}, max {:?}]",
+                timings.min, timings.median, timings.mean, timings.max
+            )?;
+        }
+    }
+
+    writeln!(out)
+}
+
+pub fn write_json(out: &mut impl Write, reports: &[DayReport]) -> std::io::Result<()> {
+    serde_json::to_writer_pretty(&mut *out, reports)?;
+    writeln!(out)
+}
+
+pub fn write_csv(out: &mut impl Write, reports: &[DayReport]) -> std::io::Result<()> {
+    writeln!(
+        out,
+        "day,part,input,answer,drawing,expected,status,error,duration_secs,\
+         bench_min_secs,bench_median_secs,bench_mean_secs,bench_max_secs,parse_time_secs,\
+         parse_bench_min_secs,parse_bench_median_secs,parse_bench_mean_secs,parse_bench_max_secs"
+    )?;
+
+    for report in reports {
+        let parse_bench = bench_fields(report.parse_bench);
+        for part in &report.parts {
+            let bench = bench_fields(part.bench);
+
+            writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{},{},{},{}",
+                report.day,
+                part.part,
+                csv_field(&report.input.to_string_lossy()),
+                csv_field(part.answer.as_deref().unwrap_or("")),
+                csv_field(part.drawing.as_deref().unwrap_or("")),
+                csv_field(part.expected.as_deref().unwrap_or("")),
+                part.status.as_str(),
+                csv_field(part.error.as_deref().unwrap_or("")),
+                part.duration.as_secs_f64(),
+                bench.join(","),
+                report
+                    .parse_time
+                    .map(|t| t.as_secs_f64().to_string())
+                    .unwrap_or_default(),
+                parse_bench.join(","),
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// How many parts ended with each status
+#[derive(Default)]
+struct StatusCounts {
+    passed: usize,
+    failed: usize,
+    unchecked: usize,
+    errors: usize,
+    crashed: usize,
+    not_implemented: usize,
+    timed_out: usize,
+}
+
+impl StatusCounts {
+    fn add(&mut self, status: Status) {
+        let count = match status {
+            Status::Pass => &mut self.passed,
+            Status::Fail => &mut self.failed,
+            Status::Unchecked => &mut self.unchecked,
+            Status::Error => &mut self.errors,
+            Status::Crashed => &mut self.crashed,
+            Status::NotImplemented => &mut self.not_implemented,
+            Status::TimedOut => &mut self.timed_out,
+        };
+        *count += 1;
+    }
+
+    fn total(&self) -> usize {
+        self.passed
+            + self.failed
+            + self.unchecked
+            + self.errors
+            + self.crashed
+            + self.not_implemented
+            + self.timed_out
+    }
+}
+
+/// Writes a table of every checked part followed by the totals for each status
+///
+/// Returns true if every part passed
+pub fn write_summary(out: &mut impl Write, reports: &[DayReport]) -> std::io::Result<bool> {
+    let mut counts = StatusCounts::default();
+
+    writeln!(out, "Verification summary")?;
+    writeln!(
+        out,
+        "  day | part | {:<20} | status          | detail",
+        "input"
+    )?;
+    writeln!(out, "  ----+------+-{:-<20}-+-----------------+-------", "")?;
+    for report in reports {
+        for part in &report.parts {
+            counts.add(part.status);
+
+            let detail = match (part.status, &part.answer, &part.expected, &part.error) {
+                (Status::Fail, Some(answer), Some(expected), _) => {
+                    format!("expected {:?}, got {:?}", expected, answer)
+                }
+                (Status::Unchecked, _, _, _) => "no expected answer".to_owned(),
+                (Status::Error | Status::Crashed | Status::TimedOut, _, _, Some(error)) => {
+                    error.clone()
+                }
+                _ => String::new(),
+            };
+            writeln!(
+                out,
+                "  {:>3} | {:>4} | {:<20} | {:<15} | {}",
+                report.day,
+                part.part,
+                report
+                    .input
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy(),
+                part.status.as_str(),
+                detail
+            )?;
+        }
+    }
+
+    writeln!(out)?;
+    writeln!(
+        out,
+        "{} passed, {} failed, {} unchecked, {} errors, {} crashed, {} not implemented, \
+         {} timed out",
+        counts.passed,
+        counts.failed,
+        counts.unchecked,
+        counts.errors,
+        counts.crashed,
+        counts.not_implemented,
+        counts.timed_out
+    )?;
+
+    Ok(counts.passed == counts.total())
+}
+
+/// The CSV fields for a set of timings, which are left empty if there aren't any
+fn bench_fields(timings: Option<Timings>) -> [String; 4] {
+    match timings {
+        Some(t) => [t.min, t.median, t.mean, t.max].map(|d| d.as_secs_f64().to_string()),
+        None => Default::default(),
+    }
+}
+
+/// Quotes a CSV field if it contains any characters that would break the row
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn timings_summarise_samples() {
+        let timings = Timings::from_samples(vec![
+            Duration::from_millis(4),
+            Duration::from_millis(1),
+            Duration::from_millis(7),
+            Duration::from_millis(2),
+        ]);
+
+        assert_eq!(timings.min, Duration::from_millis(1));
+        assert_eq!(timings.median, Duration::from_millis(3));
+        assert_eq!(
+            timings.mean,
+            Duration::from_millis(3) + Duration::from_micros(500)
+        );
+        assert_eq!(timings.max, Duration::from_millis(7));
+    }
+
+    #[test]
+    fn csv_fields_are_quoted_when_needed() {
+        assert_eq!(csv_field("12345"), "12345");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("#.\n.#\n"), "\"#.\n.#\n\"");
+    }
+}
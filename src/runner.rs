@@ -1,10 +1,51 @@
-use std::{fs, path::PathBuf};
+use std::{
+    fmt, fs,
+    io::{self, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+
+use crate::answer::Answer;
+use crate::answers::Answers;
+use crate::history;
+use crate::registry::{self, DayEntry, ParsedInput};
+use crate::report::{self, DayReport, OutputFormat, PartReport, Status, Timings};
+use crate::scaffold;
+use crate::visualise;
+use crate::watch::{self, Snapshot};
+use crate::worker::{self, Limits, Outcome, PartRun};
+
+/// A small example input from the puzzle text, stored as `inputs/day_N/<name>.txt`
+pub struct Example {
+    pub name: &'static str,
+    pub part_1: Option<&'static str>,
+    pub part_2: Option<&'static str>,
+}
 
 pub trait Day {
-    fn part_1(_input: &str) -> anyhow::Result<String> {
+    /// The puzzle input once it has been parsed, which is shared by both parts
+    type Parsed: fmt::Debug + Send + Sync + 'static;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed>;
+
+    /// Whether each part has been implemented, for `--list` to show
+    ///
+    /// Days that leave a part to its default, which panics, should set its flag to false.
+    const PART_1_IMPLEMENTED: bool = true;
+    const PART_2_IMPLEMENTED: bool = true;
+
+    fn part_1(_input: &Self::Parsed) -> anyhow::Result<Answer> {
         unimplemented!("part 1 of this day has not been implemented")
     }
-    fn part_2(_input: &str) -> anyhow::Result<String> {
+    fn part_2(_input: &Self::Parsed) -> anyhow::Result<Answer> {
         unimplemented!("part 2 of this day has not been implemented")
     }
 
@@ -15,6 +56,10 @@ pub trait Day {
     fn expected_value_part_2() -> Option<&'static str> {
         None
     }
+
+    fn examples() -> &'static [Example] {
+        &[]
+    }
 }
 
 #[derive(clap::ValueEnum, Clone, Debug)]
@@ -24,8 +69,15 @@ enum Input {
 }
 
 impl Input {
-    fn to_file_path(&self, day: u8) -> PathBuf {
-        PathBuf::from("inputs").join(match self {
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Main => "main",
+            Self::Demo => "demo",
+        }
+    }
+
+    fn to_file_path(&self, inputs_dir: &Path, day: u8) -> PathBuf {
+        inputs_dir.join(match self {
             Self::Main => format!("day_{}.txt", day),
             Self::Demo => format!("day_{}_demo.txt", day),
         })
@@ -39,8 +91,101 @@ enum Part {
     Part2,
 }
 
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Generate the module and input files for a new day, and register it
+    NewDay {
+        /// The day to create
+        day: u8,
+
+        /// The title of the day's puzzle
+        #[arg(long)]
+        title: Option<String>,
+    },
+
+    /// Show how the recorded answers and timings of each part have changed between runs
+    History {
+        /// Only show these days
+        #[arg(short, long)]
+        day: Vec<u8>,
+
+        /// Flag parts that got more than this many percent slower than the run before
+        #[arg(long, value_name = "PERCENT", default_value_t = 10.0)]
+        threshold: f64,
+    },
+
+    /// Compare two recorded runs, and exit with status 2 if any answer changed or any
+    /// part got slower than the threshold
+    Compare {
+        /// The run to compare against, numbered from 1 as in `history` [default: the
+        /// second to last run]
+        from: Option<usize>,
+
+        /// The run to compare [default: the last run]
+        to: Option<usize>,
+
+        /// Flag parts that got more than this many percent slower
+        #[arg(long, value_name = "PERCENT", default_value_t = 10.0)]
+        threshold: f64,
+    },
+
+    /// Draw how a day's puzzle is solved, for the days that support it (currently days 12
+    /// and 14)
+    Visualise {
+        /// The day to draw
+        day: u8,
+
+        /// The input to use
+        #[arg(value_enum, short, long, default_value_t=Input::Main)]
+        input: Input,
+
+        /// Read the input from this file instead (or from stdin if the path is "-")
+        #[arg(long, value_name = "PATH", conflicts_with = "input")]
+        input_file: Option<PathBuf>,
+
+        /// The part whose solution to draw
+        #[arg(
+            short,
+            long,
+            default_value_t = 1,
+            value_parser = clap::value_parser!(u8).range(1..=2)
+        )]
+        part: u8,
+
+        /// Also save the picture as a PPM image to this path
+        #[arg(long, value_name = "PATH")]
+        image: Option<PathBuf>,
+
+        /// For day 12, show how far every square is from the start (part 1) or the end
+        /// (part 2) as a histogram, and save a heatmap of it as the image
+        #[arg(long)]
+        distances: bool,
+
+        /// For day 14, how many frames of the animation to draw each second [default: 30]
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        fps: Option<u32>,
+
+        /// For day 14, how many grains of sand to drop between each frame [default: 1]
+        #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+        batch: Option<u32>,
+    },
+
+    /// Run a single part on the input given on stdin, for `--timeout` and `--memory-limit`
+    #[command(hide = true)]
+    Worker {
+        day: u8,
+        part: u8,
+
+        #[arg(long, default_value_t = 0)]
+        bench: u32,
+    },
+}
+
 #[derive(clap::Parser, Debug)]
 pub struct AdventOfCodeRunner {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     /// The day to execute
     #[arg(short, long)]
     day: Vec<u8>,
@@ -49,76 +194,766 @@ pub struct AdventOfCodeRunner {
     #[arg(value_enum, short, long, default_value_t=Input::Main)]
     input: Input,
 
+    /// Read the input from this file instead (or from stdin if the path is "-")
+    #[arg(long, value_name = "PATH", conflicts_with = "input")]
+    input_file: Option<PathBuf>,
+
+    /// Run every example input (`inputs/day_N/example_*.txt`) instead of the main input
+    #[arg(long, conflicts_with_all = ["input", "input_file"])]
+    examples: bool,
+
+    /// The directory containing the puzzle inputs
+    #[arg(
+        long,
+        value_name = "DIR",
+        env = "AOC_INPUTS_DIR",
+        default_value = "inputs",
+        global = true
+    )]
+    inputs_dir: PathBuf,
+
+    /// A TOML file of expected answers [default: <INPUTS_DIR>/answers.toml]
+    #[arg(long, value_name = "PATH", env = "AOC_ANSWERS")]
+    answers: Option<PathBuf>,
+
+    #[arg(skip)]
+    loaded_answers: Answers,
+
     /// The part to run
     #[arg(value_enum, short, long, default_value_t=Part::Both)]
     part: Part,
+
+    /// The number of parts to run at once
+    #[arg(
+        short,
+        long,
+        value_name = "N",
+        default_value_t = 1,
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    jobs: u32,
+
+    /// Parse each input and run each part N times, and report timing statistics
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u32).range(1..))]
+    bench: Option<u32>,
+
+    /// The format to print results in
+    #[arg(value_enum, short, long, default_value_t=OutputFormat::Text)]
+    output: OutputFormat,
+
+    /// List the implemented days and exit
+    #[arg(long)]
+    list: bool,
+
+    /// Check every part against its expected answer, print a summary, and exit with
+    /// status 2 if anything failed, errored, or had no expected answer
+    #[arg(long)]
+    verify: bool,
+
+    /// Print each selected input as parsed by its day, instead of running any parts
+    #[arg(long, conflicts_with_all = ["part", "bench", "verify", "record"])]
+    dump_parsed: bool,
+
+    /// Keep running, and re-run the selected days whenever their inputs or expected
+    /// answers change
+    #[arg(long)]
+    watch: bool,
+
+    /// With --watch, also rebuild and restart whenever the source code changes
+    #[arg(long, requires = "watch")]
+    rebuild: bool,
+
+    /// Kill any part that runs for longer than this, e.g. 500ms, 10s or 2m
+    ///
+    /// This covers starting the part's worker, parsing the input and solving the part once.
+    /// The repeated runs of --bench aren't limited, as the answer is known by then.
+    #[arg(long, value_name = "DURATION", value_parser = worker::parse_duration)]
+    timeout: Option<Duration>,
+
+    /// Kill any part that tries to use more than this much memory, e.g. 512M or 2G
+    #[arg(long, value_name = "SIZE", value_parser = worker::parse_size)]
+    memory_limit: Option<u64>,
+
+    /// Append the answers and timings of this run to the results log
+    #[arg(long)]
+    record: bool,
+
+    /// The results log written by --record and read by `history` and `compare`
+    #[arg(
+        long,
+        value_name = "PATH",
+        env = "AOC_RESULTS",
+        default_value = "results.jsonl",
+        global = true
+    )]
+    results: PathBuf,
+}
+
+/// The exit status used when `--verify` finds a part that did not pass
+const VERIFICATION_FAILED: u8 = 2;
+
+/// The exit status used when `compare` finds a changed answer or a slowdown
+const REGRESSION_FOUND: u8 = 2;
+
+/// How often `--watch` checks for changes
+const WATCH_INTERVAL: Duration = Duration::from_millis(300);
+
+/// Clears the terminal and moves the cursor back to the top left
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// The path used to ask for the input to be read from stdin
+const STDIN_PATH: &str = "-";
+
+fn read_input(path: &Path) -> io::Result<String> {
+    if path == Path::new(STDIN_PATH) {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input)?;
+        Ok(input)
+    } else {
+        fs::read_to_string(path)
+    }
+}
+
+/// Finds the example inputs for a day, returning each example's name and path in name order
+fn find_examples(inputs_dir: &Path, day: u8) -> io::Result<Vec<(String, PathBuf)>> {
+    let examples_dir = inputs_dir.join(format!("day_{}", day));
+    if !examples_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut examples = Vec::new();
+    for entry in fs::read_dir(examples_dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "txt") {
+            continue;
+        }
+
+        match path.file_stem().and_then(|stem| stem.to_str()) {
+            Some(name) if name.starts_with("example") => examples.push((name.to_owned(), path)),
+            _ => {}
+        }
+    }
+
+    examples.sort();
+    Ok(examples)
+}
+
+/// Looks up the expected answers for one of a day's named inputs in the answers file,
+/// falling back to the ones in the code
+fn expected_values<'a>(
+    answers: &'a Answers,
+    entry: &DayEntry,
+    input: &str,
+) -> (Option<&'a str>, Option<&'a str>) {
+    let (fallback_1, fallback_2) = if input == Input::Main.name() {
+        (
+            (entry.expected_value_part_1)(),
+            (entry.expected_value_part_2)(),
+        )
+    } else {
+        match (entry.examples)().iter().find(|e| e.name == input) {
+            Some(example) => (example.part_1, example.part_2),
+            None => (None, None),
+        }
+    };
+
+    (
+        answers.get(entry.day, input, 1).or(fallback_1),
+        answers.get(entry.day, input, 2).or(fallback_2),
+    )
+}
+
+/// One input to run a day's parts against, along with the answers we expect for it
+struct PlannedRun<'a> {
+    entry: &'static DayEntry,
+    path: PathBuf,
+    expected: (Option<&'a str>, Option<&'a str>),
+}
+
+/// Prints the parsed form of each planned input, for debugging parsers
+fn dump_parsed(runs: &[PlannedRun]) -> anyhow::Result<ExitCode> {
+    let mut out = io::stdout().lock();
+    let mut all_parsed = true;
+    for run in runs {
+        writeln!(out, "Day {} ({:?}):", run.entry.day, run.path)?;
+        let parsed = read_input(&run.path)
+            .with_context(|| format!("could not read {:?}", run.path))
+            .and_then(|input| (run.entry.parse)(&input));
+        match parsed {
+            Ok(parsed) => writeln!(out, "{:#?}", parsed)?,
+            Err(err) => {
+                writeln!(out, "  !ERROR! {:#}", err)?;
+                all_parsed = false;
+            }
+        }
+        writeln!(out)?;
+    }
+
+    Ok(if all_parsed {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    })
+}
+
+fn time<T>(f: impl FnOnce() -> T) -> (T, Duration) {
+    let start = Instant::now();
+    let result = f();
+    (result, start.elapsed())
 }
 
 impl AdventOfCodeRunner {
-    pub fn run(mut self) -> anyhow::Result<()> {
-        if self.day.is_empty() {
-            self.day = (1..=15).collect()
-        }
-
-        for day in &self.day {
-            match day {
-                1 => self.execute::<crate::day01::Day1>(*day)?,
-                2 => self.execute::<crate::day02::Day2>(*day)?,
-                3 => self.execute::<crate::day03::Day3>(*day)?,
-                4 => self.execute::<crate::day04::Day4>(*day)?,
-                5 => self.execute::<crate::day05::Day5>(*day)?,
-                6 => self.execute::<crate::day06::Day6>(*day)?,
-                7 => self.execute::<crate::day07::Day7>(*day)?,
-                8 => self.execute::<crate::day08::Day8>(*day)?,
-                9 => self.execute::<crate::day09::Day9>(*day)?,
-                10 => self.execute::<crate::day10::Day10>(*day)?,
-                11 => self.execute::<crate::day11::Day11>(*day)?,
-                12 => self.execute::<crate::day12::Day12>(*day)?,
-                13 => self.execute::<crate::day13::Day13>(*day)?,
-                14 => self.execute::<crate::day14::Day14>(*day)?,
-                15 => self.execute::<crate::day15::Day15>(*day)?,
-                n => panic!("Day not yet implemented: {}", n),
-            };
+    pub fn run(self) -> anyhow::Result<ExitCode> {
+        match &self.command {
+            Some(Command::NewDay { day, title }) => {
+                let title = title.clone().unwrap_or_else(|| format!("Day {}", day));
+                scaffold::new_day(
+                    Path::new(env!("CARGO_MANIFEST_DIR")),
+                    &self.inputs_dir,
+                    *day,
+                    &title,
+                )?;
+                return Ok(ExitCode::SUCCESS);
+            }
+            Some(Command::History { day, threshold }) => {
+                let records = history::load(&self.results)?;
+                history::write_history(&mut io::stdout().lock(), &records, day, *threshold)?;
+                return Ok(ExitCode::SUCCESS);
+            }
+            Some(Command::Compare {
+                from,
+                to,
+                threshold,
+            }) => {
+                let records = history::load(&self.results)?;
+                let runs = history::runs(&records).len();
+                if runs == 0 || (runs < 2 && from.is_none()) {
+                    anyhow::bail!(
+                        "there are {} runs in {:?}, so there is nothing to compare",
+                        runs,
+                        self.results
+                    );
+                }
+
+                let to = to.unwrap_or(runs);
+                let from = from.unwrap_or_else(|| runs - 1);
+                let out = &mut io::stdout().lock();
+                if !history::write_comparison(out, &records, from, to, *threshold)? {
+                    return Ok(ExitCode::from(REGRESSION_FOUND));
+                }
+                return Ok(ExitCode::SUCCESS);
+            }
+            Some(Command::Visualise {
+                day,
+                input,
+                input_file,
+                part,
+                image,
+                distances,
+                fps,
+                batch,
+            }) => {
+                let path = match input_file {
+                    Some(path) => path.clone(),
+                    None => input.to_file_path(&self.inputs_dir, *day),
+                };
+                let input =
+                    read_input(&path).with_context(|| format!("could not read {:?}", path))?;
+                let options = visualise::Options {
+                    part: *part,
+                    image: image.clone(),
+                    terminal: io::stdout().is_terminal(),
+                    interactive: io::stdin().is_terminal(),
+                    distances: *distances,
+                    fps: *fps,
+                    batch: *batch,
+                };
+                visualise::visualise(&mut io::stdout().lock(), *day, &input, &options)?;
+                return Ok(ExitCode::SUCCESS);
+            }
+            Some(Command::Worker { day, part, bench }) => {
+                worker::serve(*day, *part, *bench)?;
+                return Ok(ExitCode::SUCCESS);
+            }
+            None => {}
         }
 
+        if self.list {
+            self.list_days()?;
+            return Ok(ExitCode::SUCCESS);
+        }
+
+        self.run_days()
+    }
+
+    fn list_days(&self) -> anyhow::Result<()> {
+        let answers = self.load_answers()?;
+        let mut out = io::stdout().lock();
+        for entry in crate::DAYS {
+            let (expected_1, expected_2) = expected_values(&answers, entry, Input::Main.name());
+            let examples = find_examples(&self.inputs_dir, entry.day)?;
+            writeln!(
+                out,
+                "Day {:>2}: {:<26} [part 1 {}] [part 2 {}] [{} example{}]",
+                entry.day,
+                entry.title,
+                part_marker(entry.implemented.0, expected_1),
+                part_marker(entry.implemented.1, expected_2),
+                examples.len(),
+                if examples.len() == 1 { "" } else { "s" },
+            )?;
+        }
+        writeln!(out)?;
+        writeln!(
+            out,
+            "✓ = implemented, and the answer for the main input is known"
+        )?;
+        writeln!(
+            out,
+            "? = implemented, but the answer for the main input isn't known"
+        )?;
+        writeln!(out, "✗ = not implemented yet")?;
         Ok(())
     }
 
-    fn execute<D: Day>(&self, day: u8) -> anyhow::Result<()> {
-        let file_path = self.input.to_file_path(day);
-        let input = fs::read_to_string(&file_path)?;
+    fn answers_path(&self) -> PathBuf {
+        match &self.answers {
+            Some(path) => path.clone(),
+            None => self.inputs_dir.join("answers.toml"),
+        }
+    }
+
+    fn load_answers(&self) -> anyhow::Result<Answers> {
+        Answers::load(&self.answers_path())
+    }
+
+    fn run_days(mut self) -> anyhow::Result<ExitCode> {
+        let days = if self.day.is_empty() {
+            crate::DAYS.iter().collect()
+        } else {
+            self.day
+                .iter()
+                .map(|&day| {
+                    registry::find(crate::DAYS, day).ok_or_else(|| {
+                        anyhow::anyhow!(
+                            "day {} has not been implemented (use --list to see the days that have)",
+                            day
+                        )
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()?
+        };
+
+        if self.input_file.is_some() && days.len() != 1 {
+            anyhow::bail!("--input-file can only be used with a single --day");
+        }
+
+        if self.watch {
+            return self.watch_days(&days);
+        }
 
-        println!("Executing day {}", day);
-        println!("  [using input {:?}]", &file_path);
-        println!();
+        self.run_selected(&days)
+    }
 
-        if self.part == Part::Part1 || self.part == Part::Both {
-            println!("Executing part 1");
-            let output = <D as Day>::part_1(&input)?;
-            println!("  {}", output);
-            if let Some(expected) = <D as Day>::expected_value_part_1() {
-                println!(
-                    "  [expected = {}{}]",
-                    expected,
-                    if expected == output { "" } else { " !FAILED!" }
-                )
+    /// Runs the selected days, then runs them again each time one of their inputs changes
+    fn watch_days(&mut self, days: &[&'static DayEntry]) -> anyhow::Result<ExitCode> {
+        if self.input_file.as_deref() == Some(Path::new(STDIN_PATH)) {
+            anyhow::bail!("--watch cannot be used when reading the input from stdin");
+        }
+
+        let manifest_dir = Path::new(env!("CARGO_MANIFEST_DIR"));
+        loop {
+            print!("{}", CLEAR_SCREEN);
+            if let Err(err) = self.run_selected(days) {
+                println!("Error: {:#}", err);
+            }
+            println!("[watching for changes, press Ctrl-C to stop]");
+            io::stdout().flush()?;
+
+            let inputs = Snapshot::take(self.watched_paths(days));
+            let mut source = self
+                .rebuild
+                .then(|| Snapshot::take(vec![manifest_dir.join("src")]));
+            loop {
+                thread::sleep(WATCH_INTERVAL);
+
+                if source.as_ref().is_some_and(Snapshot::has_changed) {
+                    println!("[source changed, rebuilding]");
+                    watch::rebuild_and_restart(manifest_dir)?;
+                    // the build failed, so wait for the source to change again
+                    source = Some(Snapshot::take(vec![manifest_dir.join("src")]));
+                }
+
+                if inputs.has_changed() {
+                    break;
+                }
             }
         }
+    }
+
+    /// The files that the selected days read, so that `--watch` can tell when to re-run them
+    fn watched_paths(&self, days: &[&'static DayEntry]) -> Vec<PathBuf> {
+        let mut paths = vec![self.answers_path()];
+        for entry in days {
+            paths.push(match &self.input_file {
+                _ if self.examples => self.inputs_dir.join(format!("day_{}", entry.day)),
+                Some(path) => path.clone(),
+                None => self.input.to_file_path(&self.inputs_dir, entry.day),
+            });
+        }
+        paths
+    }
+
+    fn run_selected(&mut self, days: &[&'static DayEntry]) -> anyhow::Result<ExitCode> {
+        self.loaded_answers = self.load_answers()?;
 
-        if self.part == Part::Part2 || self.part == Part::Both {
-            println!("Executing part 2");
-            let output = <D as Day>::part_2(&input)?;
-            println!("  {}", output);
-            if let Some(expected) = <D as Day>::expected_value_part_2() {
-                println!(
-                    "  [expected = {}{}]",
-                    expected,
-                    if expected == output { "" } else { " !FAILED!" }
-                )
+        let mut runs = Vec::new();
+        for &entry in days {
+            runs.extend(self.plan_day(entry)?);
+        }
+
+        if self.dump_parsed {
+            return dump_parsed(&runs);
+        }
+
+        let reports = self.execute(&runs)?;
+
+        if self.record {
+            let commit = history::git_commit(Path::new(env!("CARGO_MANIFEST_DIR")));
+            history::append(&self.results, &history::records(&reports, commit))?;
+        }
+
+        match self.output {
+            OutputFormat::Text => {}
+            OutputFormat::Json => report::write_json(&mut io::stdout().lock(), &reports)?,
+            OutputFormat::Csv => report::write_csv(&mut io::stdout().lock(), &reports)?,
+        }
+
+        if self.verify {
+            // keep machine-readable output on stdout clean
+            let mut out: Box<dyn Write> = match self.output {
+                OutputFormat::Text => Box::new(io::stdout().lock()),
+                _ => Box::new(io::stderr().lock()),
+            };
+            if !report::write_summary(&mut out, &reports)? {
+                return Ok(ExitCode::from(VERIFICATION_FAILED));
             }
         }
 
-        println!();
-        Ok(())
+        Ok(ExitCode::SUCCESS)
+    }
+
+    /// Works out which inputs a day should be run against, and the answers expected for each
+    fn plan_day(&self, entry: &'static DayEntry) -> anyhow::Result<Vec<PlannedRun<'_>>> {
+        let day = entry.day;
+        if self.examples {
+            let examples = find_examples(&self.inputs_dir, day)?;
+            if examples.is_empty() {
+                eprintln!("No example inputs found for day {}", day);
+            }
+
+            return Ok(examples
+                .into_iter()
+                .map(|(name, path)| PlannedRun {
+                    entry,
+                    path,
+                    expected: expected_values(&self.loaded_answers, entry, &name),
+                })
+                .collect());
+        }
+
+        Ok(vec![match &self.input_file {
+            // the answers in the code belong to the puzzle inputs, so they say nothing
+            // about whatever arbitrary file we've been given, but the answers file might
+            Some(path) => {
+                let name = match path.file_stem() {
+                    Some(stem) if path != Path::new(STDIN_PATH) => stem.to_string_lossy(),
+                    _ => Default::default(),
+                };
+                PlannedRun {
+                    entry,
+                    path: path.clone(),
+                    expected: (
+                        self.loaded_answers.get(day, &name, 1),
+                        self.loaded_answers.get(day, &name, 2),
+                    ),
+                }
+            }
+            None => PlannedRun {
+                entry,
+                path: self.input.to_file_path(&self.inputs_dir, day),
+                expected: expected_values(&self.loaded_answers, entry, self.input.name()),
+            },
+        }])
+    }
+
+    /// Runs the selected parts for every planned input, spread across `--jobs` threads
+    ///
+    /// Results are returned (and, for text output, printed) in the order they were planned,
+    /// regardless of which order they finish in.
+    fn execute(&self, runs: &[PlannedRun]) -> anyhow::Result<Vec<DayReport>> {
+        let parts: &[u8] = match self.part {
+            Part::Both => &[1, 2],
+            Part::Part1 => &[1],
+            Part::Part2 => &[2],
+        };
+
+        // inputs are read up front, as stdin can only be read once
+        let inputs = runs
+            .iter()
+            .map(|run| time(|| read_input(&run.path)))
+            .collect::<Vec<_>>();
+
+        // each input is parsed once and shared by both parts, unless the parts are running
+        // in workers, which have to parse the input for themselves
+        let limits = self.limits();
+        let bench = self.bench.unwrap_or(0);
+        let parsed = inputs
+            .iter()
+            .zip(runs)
+            .map(|((input, _), run)| match input {
+                Ok(input) if !limits.is_set() => Some(worker::parse(run.entry, input)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        let mut reports = runs
+            .iter()
+            .zip(&inputs)
+            .zip(&parsed)
+            .map(|((run, (input, load_time)), parsed)| DayReport {
+                day: run.entry.day,
+                input: run.path.clone(),
+                load_time: *load_time,
+                parse_time: parsed.as_ref().map(|(_, parse_time)| *parse_time),
+                parse_bench: match (input, parsed) {
+                    (Ok(input), Some((Ok(_), parse_time))) => {
+                        timings(worker::bench_parse(run.entry, input, *parse_time, bench))
+                    }
+                    _ => None,
+                },
+                input_hash: input
+                    .as_ref()
+                    .ok()
+                    .map(|input| history::fnv1a(input.as_bytes())),
+                parts: Vec::new(),
+            })
+            .collect::<Vec<_>>();
+
+        // job `idx` is part `parts[idx % parts.len()]` of run `idx / parts.len()`
+        let job_count = runs.len() * parts.len();
+        let next_job = AtomicUsize::new(0);
+        let mut results = (0..job_count).map(|_| None).collect::<Vec<_>>();
+        let mut finished_runs = 0;
+
+        thread::scope(|scope| -> anyhow::Result<()> {
+            let (sender, receiver) = mpsc::channel();
+            for _ in 0..self.jobs {
+                let sender = sender.clone();
+                let next_job = &next_job;
+                let inputs = &inputs;
+                let parsed = &parsed;
+                scope.spawn(move || loop {
+                    let idx = next_job.fetch_add(1, Ordering::Relaxed);
+                    if idx >= job_count {
+                        break;
+                    }
+
+                    let run = &runs[idx / parts.len()];
+                    let part = parts[idx % parts.len()];
+                    let expected = match part {
+                        1 => run.expected.0,
+                        _ => run.expected.1,
+                    };
+                    let input = &inputs[idx / parts.len()].0;
+                    let parsed = parsed[idx / parts.len()].as_ref().map(|(parsed, _)| parsed);
+                    let report = self.execute_part(run.entry, part, input, parsed, expected);
+                    if sender.send((idx, report)).is_err() {
+                        break;
+                    }
+                });
+            }
+            drop(sender);
+
+            for (idx, report) in receiver {
+                results[idx] = Some(report);
+
+                while finished_runs < runs.len() {
+                    let run_results = &mut results[finished_runs * parts.len()..][..parts.len()];
+                    if run_results.iter().any(Option::is_none) {
+                        break;
+                    }
+
+                    let report = &mut reports[finished_runs];
+                    for (part, parse) in run_results.iter_mut().filter_map(Option::take) {
+                        if let (None, Some((parse_time, samples))) = (report.parse_time, parse) {
+                            report.parse_time = Some(parse_time);
+                            report.parse_bench = timings(samples);
+                        }
+                        report.parts.push(part);
+                    }
+                    // text output is streamed so that long runs show progress as they go
+                    if self.output == OutputFormat::Text {
+                        report::write_text(&mut io::stdout().lock(), report)?;
+                    }
+                    finished_runs += 1;
+                }
+            }
+
+            Ok(())
+        })?;
+
+        Ok(reports)
+    }
+
+    fn limits(&self) -> Limits {
+        Limits {
+            timeout: self.timeout,
+            memory: self.memory_limit,
+        }
+    }
+
+    /// Runs a single part, returning its report and, if it had to parse the input itself,
+    /// how long that took along with the timings of any `--bench` parses
+    ///
+    /// `parsed` is the input already parsed by this process, which is only missing when
+    /// parts are run in workers or the input couldn't be read.
+    fn execute_part(
+        &self,
+        entry: &DayEntry,
+        part: u8,
+        input: &io::Result<String>,
+        parsed: Option<&Result<Box<dyn ParsedInput>, Outcome>>,
+        expected: Option<&str>,
+    ) -> (PartReport, Option<(Duration, Vec<Duration>)>) {
+        let day = entry.day;
+        let mut report = PartReport {
+            part,
+            answer: None,
+            drawing: None,
+            expected: expected.map(str::to_owned),
+            status: Status::Error,
+            error: None,
+            duration: Duration::ZERO,
+            bench: None,
+        };
+
+        let input = match input {
+            Ok(input) => input,
+            Err(err) => {
+                report.error = Some(format!("could not read input: {}", err));
+                return (report, None);
+            }
+        };
+
+        // each part runs in isolation, so a panicking day doesn't take the others down with
+        // it, and with limits set it runs in a worker process that can be killed
+        let bench = self.bench.unwrap_or(0);
+        let run = match parsed {
+            Some(Ok(parsed)) => worker::run_part(entry.part(part), &**parsed, bench),
+            Some(Err(outcome)) => PartRun::not_run(outcome.clone()),
+            None => match worker::supervise(entry, part, input, bench, self.limits()) {
+                Ok(run) => run,
+                Err(err) => {
+                    report.error = Some(format!("day {}: {:#}", day, err));
+                    return (report, None);
+                }
+            },
+        };
+
+        report.duration = run.duration;
+        report.bench = timings(run.samples);
+
+        match run.outcome {
+            Outcome::Answer(answer) => {
+                report.status = Status::check(&answer, expected);
+                report.answer = Some(answer.to_string());
+                if let Answer::Grid(_) = answer {
+                    report.drawing = Some(format!("{:#}", answer));
+                }
+            }
+            Outcome::Error(err) => report.error = Some(format!("day {}: {}", day, err)),
+            Outcome::Panicked(panic) => {
+                report.status = if panic.is_unimplemented() {
+                    Status::NotImplemented
+                } else {
+                    Status::Crashed
+                };
+                report.error = Some(format!("day {}: {}", day, panic));
+            }
+            Outcome::TimedOut(timeout) => {
+                report.status = Status::TimedOut;
+                report.error = Some(format!("day {}: took longer than {:?}", day, timeout));
+            }
+            Outcome::Died(reason) => {
+                report.status = Status::Crashed;
+                report.error = Some(format!("day {}: {}", day, reason));
+            }
+        }
+
+        let parse = run
+            .parse_duration
+            .map(|parse_time| (parse_time, run.parse_samples));
+        (report, parse)
+    }
+}
+
+/// How far along a part is, for `--list`
+fn part_marker(implemented: bool, expected: Option<&str>) -> char {
+    match (implemented, expected) {
+        (false, _) => '✗',
+        (true, Some(_)) => '✓',
+        (true, None) => '?',
+    }
+}
+
+/// Summarises the timings of `--bench` runs, if there were any
+fn timings(samples: Vec<Duration>) -> Option<Timings> {
+    (!samples.is_empty()).then(|| Timings::from_samples(samples))
+}
+
+/// Runs both parts of a day against its main, demo and example inputs, and asserts that
+/// every answer we have an expectation for matches. Inputs that aren't present are skipped.
+#[cfg(test)]
+pub fn assert_expected_answers(entry: &DayEntry) {
+    let day = entry.day;
+    let inputs_dir = Path::new("inputs");
+    let answers = Answers::load(&inputs_dir.join("answers.toml")).unwrap();
+
+    let mut inputs = find_examples(inputs_dir, day).unwrap();
+    for input in [Input::Main, Input::Demo] {
+        inputs.push((input.name().to_owned(), input.to_file_path(inputs_dir, day)));
+    }
+
+    for (name, path) in inputs {
+        let input = match fs::read_to_string(&path) {
+            Ok(input) => input,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                eprintln!("skipping day {} {:?}: input not found", day, path);
+                continue;
+            }
+            Err(err) => panic!("could not read {:?}: {}", path, err),
+        };
+
+        let (expected_1, expected_2) = expected_values(&answers, entry, &name);
+        if expected_1.is_none() && expected_2.is_none() {
+            continue;
+        }
+
+        let parsed = (entry.parse)(&input).unwrap();
+        for (part, expected) in [(1, expected_1), (2, expected_2)] {
+            if let Some(expected) = expected {
+                let answer = entry.part(part)(&*parsed).unwrap();
+                assert!(
+                    answer.matches(expected),
+                    "day {} part {} with {:?}: got {}, expected {}",
+                    day,
+                    part,
+                    path,
+                    answer,
+                    expected
+                );
+            }
+        }
     }
 }
@@ -0,0 +1,141 @@
+use std::{
+    fs::{self, OpenOptions},
+    path::Path,
+};
+
+use anyhow::Context;
+
+/// The line that opens the registry in `lib.rs`
+const REGISTRY_START: &str = "registry::days! {";
+
+fn module_template(day: u8) -> String {
+    format!(
+        "pub struct Day{day};
+
+impl crate::runner::Day for Day{day} {{
+    type Parsed = String;
+
+    // remove these as each part is implemented
+    const PART_1_IMPLEMENTED: bool = false;
+    const PART_2_IMPLEMENTED: bool = false;
+
+    fn parse(input: &str) -> anyhow::Result<Self::Parsed> {{
+        Ok(input.to_owned())
+    }}
+}}
+"
+    )
+}
+
+/// Creates the module for a new day, registers it, and creates empty input files for it
+///
+/// Nothing is written if any of the files we would create already exist.
+pub fn new_day(project_dir: &Path, inputs_dir: &Path, day: u8, title: &str) -> anyhow::Result<()> {
+    if !(1..=25).contains(&day) {
+        anyhow::bail!("day must be between 1 and 25, got {}", day);
+    }
+
+    let module = format!("day{:02}", day);
+    let module_path = project_dir.join("src").join(format!("{}.rs", module));
+    let lib_path = project_dir.join("src").join("lib.rs");
+    let input_paths = [
+        inputs_dir.join(format!("day_{}.txt", day)),
+        inputs_dir.join(format!("day_{}_demo.txt", day)),
+    ];
+
+    for path in std::iter::once(&module_path).chain(&input_paths) {
+        if path.exists() {
+            anyhow::bail!("{:?} already exists, refusing to overwrite it", path);
+        }
+    }
+
+    let lib =
+        fs::read_to_string(&lib_path).with_context(|| format!("could not read {:?}", lib_path))?;
+    let line = format!("    {} => {}::Day{}, {:?};", day, module, day, title);
+    let lib = register_day(&lib, day, &line)?;
+
+    fs::write(&module_path, module_template(day))
+        .with_context(|| format!("could not write {:?}", module_path))?;
+    fs::write(&lib_path, lib).with_context(|| format!("could not write {:?}", lib_path))?;
+    fs::create_dir_all(inputs_dir)?;
+    for path in &input_paths {
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(path)
+            .with_context(|| format!("could not create {:?}", path))?;
+    }
+
+    println!("Created {:?}", module_path);
+    for path in &input_paths {
+        println!("Created {:?}", path);
+    }
+    println!("Registered day {} in {:?}", day, lib_path);
+
+    Ok(())
+}
+
+/// Inserts a registry line into the source of `lib.rs`, keeping the days in order
+fn register_day(lib: &str, day: u8, new_line: &str) -> anyhow::Result<String> {
+    let mut lines = lib.lines().collect::<Vec<_>>();
+    let start = lines
+        .iter()
+        .position(|line| line.trim() == REGISTRY_START)
+        .context("could not find the day registry in lib.rs")?;
+
+    let mut insert_at = None;
+    for (idx, line) in lines.iter().enumerate().skip(start + 1) {
+        if line.trim() == "}" {
+            insert_at.get_or_insert(idx);
+            break;
+        }
+
+        let existing = line
+            .trim()
+            .split_once(" =>")
+            .and_then(|(n, _)| n.parse::<u8>().ok());
+        match existing {
+            Some(n) if n == day => anyhow::bail!("day {} is already registered", day),
+            Some(n) if n > day => {
+                insert_at.get_or_insert(idx);
+            }
+            _ => {}
+        }
+    }
+
+    let insert_at = insert_at.context("could not find the end of the day registry in lib.rs")?;
+    lines.insert(insert_at, new_line);
+
+    let mut lib = lines.join("\n");
+    lib.push('\n');
+    Ok(lib)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIB: &str = "pub mod runner;
+
+registry::days! {
+    1 => day01::Day1, \"One\";
+    3 => day03::Day3, \"Three\";
+}
+
+pub use runner::Day;
+";
+
+    #[test]
+    fn registers_days_in_order() {
+        let lib = register_day(LIB, 2, "    2 => day02::Day2, \"Two\";").unwrap();
+        assert!(lib.contains("\"One\";\n    2 => day02::Day2, \"Two\";\n    3 =>"));
+
+        let lib = register_day(LIB, 4, "    4 => day04::Day4, \"Four\";").unwrap();
+        assert!(lib.contains("\"Three\";\n    4 => day04::Day4, \"Four\";\n}"));
+    }
+
+    #[test]
+    fn refuses_to_register_a_day_twice() {
+        assert!(register_day(LIB, 3, "    3 => day03::Day3, \"Three\";").is_err());
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    cmp::Reverse,
+    collections::{hash_map::Entry, BinaryHeap, HashMap, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+/// The cheapest way found from a start to a goal
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Path<N, C> {
+    /// The total cost of every step taken
+    pub cost: C,
+    /// Every node visited along the way, from the start to the goal inclusive
+    pub nodes: Vec<N>,
+}
+
+impl<N, C> Path<N, C> {
+    /// The number of steps taken, which is one fewer than the number of nodes
+    pub fn steps(&self) -> usize {
+        self.nodes.len() - 1
+    }
+}
+
+/// Follows the predecessors back from `goal` to the start, which is the one without any
+fn reconstruct<N: Eq + Hash + Clone, C: Copy>(
+    seen: &HashMap<N, (Option<N>, C)>,
+    goal: N,
+) -> Path<N, C> {
+    let cost = seen[&goal].1;
+    let mut nodes = vec![goal];
+    while let Some(previous) = &seen[nodes.last().unwrap()].0 {
+        nodes.push(previous.clone());
+    }
+    nodes.reverse();
+
+    Path { cost, nodes }
+}
+
+/// Finds the path with the fewest steps from `start` to a node that `is_goal`, by
+/// breadth-first search
+///
+/// Returns `None` if no goal can be reached.
+pub fn bfs<N, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, usize>>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut seen = HashMap::from([(start.clone(), (None, 0))]);
+    let mut queue = VecDeque::from([start]);
+
+    while let Some(node) = queue.pop_front() {
+        if is_goal(&node) {
+            return Some(reconstruct(&seen, node));
+        }
+
+        let steps = seen[&node].1 + 1;
+        for neighbour in neighbours(&node) {
+            if let Entry::Vacant(entry) = seen.entry(neighbour.clone()) {
+                entry.insert((Some(node.clone()), steps));
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds how many steps it takes to reach every node that can be reached from the nearest
+/// of `sources`, by breadth-first search
+pub fn bfs_distances<N, I>(
+    sources: impl IntoIterator<Item = N>,
+    mut neighbours: impl FnMut(&N) -> I,
+) -> HashMap<N, usize>
+where
+    N: Eq + Hash + Clone,
+    I: IntoIterator<Item = N>,
+{
+    let mut distances = HashMap::new();
+    let mut queue = VecDeque::new();
+    for source in sources {
+        if distances.insert(source.clone(), 0).is_none() {
+            queue.push_back(source);
+        }
+    }
+
+    while let Some(node) = queue.pop_front() {
+        let steps = distances[&node] + 1;
+        for neighbour in neighbours(&node) {
+            if let Entry::Vacant(entry) = distances.entry(neighbour.clone()) {
+                entry.insert(steps);
+                queue.push_back(neighbour);
+            }
+        }
+    }
+
+    distances
+}
+
+/// Finds the cheapest path from `start` to a node that `is_goal`, where `neighbours`
+/// gives each node reachable in one step along with the cost of that step
+///
+/// Costs must not be negative. Returns `None` if no goal can be reached.
+pub fn dijkstra<N, C, I>(
+    start: N,
+    neighbours: impl FnMut(&N) -> I,
+    is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    astar(start, neighbours, |_| C::default(), is_goal)
+}
+
+/// Like [`dijkstra`], but guided towards the goal by `heuristic`, which estimates the
+/// cost of getting from a node to the nearest goal
+///
+/// The path is only guaranteed to be the cheapest if the heuristic never overestimates.
+pub fn astar<N, C, I>(
+    start: N,
+    mut neighbours: impl FnMut(&N) -> I,
+    mut heuristic: impl FnMut(&N) -> C,
+    mut is_goal: impl FnMut(&N) -> bool,
+) -> Option<Path<N, C>>
+where
+    N: Eq + Hash + Clone,
+    C: Ord + Copy + Default + Add<Output = C>,
+    I: IntoIterator<Item = (N, C)>,
+{
+    let mut seen = HashMap::from([(start.clone(), (None, C::default()))]);
+    // nodes aren't required to be `Ord`, so the heap refers to them by their index here
+    let mut queued = vec![start];
+    let mut heap = BinaryHeap::from([(Reverse(heuristic(&queued[0])), Reverse(C::default()), 0)]);
+
+    while let Some((_, Reverse(cost), idx)) = heap.pop() {
+        let node = queued[idx].clone();
+        if cost > seen[&node].1 {
+            // we've since found a cheaper way here
+            continue;
+        }
+        if is_goal(&node) {
+            return Some(reconstruct(&seen, node));
+        }
+
+        for (neighbour, step_cost) in neighbours(&node) {
+            let new_cost = cost + step_cost;
+            match seen.entry(neighbour.clone()) {
+                Entry::Occupied(entry) if entry.get().1 <= new_cost => continue,
+                Entry::Occupied(mut entry) => {
+                    entry.insert((Some(node.clone()), new_cost));
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert((Some(node.clone()), new_cost));
+                }
+            }
+
+            let estimate = new_cost + heuristic(&neighbour);
+            heap.push((Reverse(estimate), Reverse(new_cost), queued.len()));
+            queued.push(neighbour);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::grid::{Grid, Point};
+
+    const MAZE: &str = "\
+        ..#.\n\
+        .##.\n\
+        ....\n";
+
+    fn open_neighbours(maze: &Grid<bool>, point: Point) -> Vec<Point> {
+        maze.neighbours4(point).filter(|&n| maze[n]).collect()
+    }
+
+    #[test]
+    fn bfs_finds_the_fewest_steps() {
+        let maze = Grid::parse(MAZE, "a wall or floor", |_, c| Some(c == '.')).unwrap();
+        let path = bfs((0, 0), |&p| open_neighbours(&maze, p), |&p| p == (3, 0)).unwrap();
+
+        assert_eq!(path.cost, 7);
+        assert_eq!(path.steps(), 7);
+        assert_eq!(path.nodes.first(), Some(&(0, 0)));
+        assert_eq!(path.nodes.last(), Some(&(3, 0)));
+        assert_eq!(
+            bfs((0, 0), |&p| open_neighbours(&maze, p), |_| true)
+                .unwrap()
+                .nodes,
+            [(0, 0)]
+        );
+    }
+
+    #[test]
+    fn measures_distances_from_the_nearest_source() {
+        let maze = Grid::parse(MAZE, "a wall or floor", |_, c| Some(c == '.')).unwrap();
+        let distances = bfs_distances([(0, 0), (3, 0)], |&p| open_neighbours(&maze, p));
+
+        assert_eq!(distances.len(), 9);
+        assert_eq!(distances[&(3, 0)], 0);
+        assert_eq!(distances[&(2, 2)], 3);
+        assert_eq!(distances.get(&(1, 1)), None);
+    }
+
+    #[test]
+    fn weighted_searches_find_the_cheapest_path() {
+        // going straight from 0 to 3 costs 10, but going via 1 and 2 costs 3
+        let edges = HashMap::from([
+            (0, vec![(3, 10), (1, 1)]),
+            (1, vec![(2, 1)]),
+            (2, vec![(3, 1)]),
+            (3, vec![]),
+        ]);
+        let neighbours = |n: &u32| edges[n].clone();
+
+        let path = dijkstra(0, neighbours, |&n| n == 3).unwrap();
+        assert_eq!(
+            path,
+            Path {
+                cost: 3,
+                nodes: vec![0, 1, 2, 3]
+            }
+        );
+
+        let path = astar(0, neighbours, |&n| 3 - n, |&n| n == 3).unwrap();
+        assert_eq!(
+            path,
+            Path {
+                cost: 3,
+                nodes: vec![0, 1, 2, 3]
+            }
+        );
+    }
+
+    #[test]
+    fn returns_none_when_the_goal_is_unreachable() {
+        let maze = Grid::parse(MAZE, "a wall or floor", |_, c| Some(c == '.')).unwrap();
+        let unreachable = |&p: &Point| p == (2, 0);
+
+        assert_eq!(
+            bfs((0, 0), |&p| open_neighbours(&maze, p), unreachable),
+            None
+        );
+        assert_eq!(
+            dijkstra(
+                (0, 0),
+                |&p| open_neighbours(&maze, p).into_iter().map(|n| (n, 1)),
+                unreachable
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,499 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::mpsc::{self, Receiver, RecvTimeoutError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+
+use crate::{
+    day12::{self, HeightMap, Part1Strategy, Part2Strategy, Strategy},
+    day14::CaveSlice,
+    grid::Grid,
+    image::{self, Rgb},
+};
+
+/// How many pixels wide and tall each cell is drawn in an image
+const IMAGE_SCALE: usize = 4;
+
+/// The most rows a histogram is drawn with, beyond which values are grouped together
+const HISTOGRAM_ROWS: usize = 20;
+/// How long the longest bar of a histogram is
+const HISTOGRAM_WIDTH: usize = 50;
+
+/// Moves the cursor to the top left, so the next frame is drawn over the last one
+const CURSOR_HOME: &str = "\x1b[H";
+const CLEAR_SCREEN: &str = "\x1b[2J";
+/// Clears from the cursor to the end of the line
+const CLEAR_LINE: &str = "\x1b[K";
+const HIDE_CURSOR: &str = "\x1b[?25l";
+const SHOW_CURSOR: &str = "\x1b[?25h";
+
+const DEFAULT_FPS: u32 = 30;
+const DEFAULT_BATCH: u32 = 1;
+
+/// What to draw, and where
+#[derive(Debug, Clone)]
+pub struct Options {
+    pub part: u8,
+    /// Also save the picture as an image to this path
+    pub image: Option<PathBuf>,
+    /// Whether the output is going to a terminal, and so can be coloured and animated
+    pub terminal: bool,
+    /// Whether keys can be read from a terminal, to control animations
+    pub interactive: bool,
+    /// For day 12, draw how far away every square is instead of a single route
+    pub distances: bool,
+    /// For day 14, how many frames to draw each second
+    pub fps: Option<u32>,
+    /// For day 14, how many grains of sand to drop between each frame
+    pub batch: Option<u32>,
+}
+
+/// Draws how a day's puzzle is solved for the given input, for the days that support it
+pub fn visualise(
+    out: &mut impl Write,
+    day: u8,
+    input: &str,
+    options: &Options,
+) -> anyhow::Result<()> {
+    // options for other days are rejected rather than ignored, so nobody waits on an image
+    // that will never be written
+    let unsupported = match day {
+        12 => vec![
+            ("--fps", options.fps.is_some()),
+            ("--batch", options.batch.is_some()),
+        ],
+        14 => vec![
+            ("--image", options.image.is_some()),
+            ("--distances", options.distances),
+        ],
+        _ => Vec::new(),
+    };
+    if let Some((option, _)) = unsupported.into_iter().find(|&(_, given)| given) {
+        anyhow::bail!("{} can't be used when visualising day {}", option, day);
+    }
+
+    match day {
+        12 => visualise_day_12(out, input, options),
+        14 => visualise_day_14(out, input, options),
+        _ => anyhow::bail!("there is no visualisation for day {}", day),
+    }
+}
+
+fn save_image(path: &Path, pixels: &Grid<Rgb>) -> anyhow::Result<()> {
+    File::create(path)
+        .map(BufWriter::new)
+        .and_then(|mut file| {
+            image::write_ppm(&mut file, pixels, IMAGE_SCALE)?;
+            file.flush()
+        })
+        .with_context(|| format!("could not write {:?}", path))
+}
+
+/// Draws a bar for each distance (or range of distances), showing how many things are that
+/// far away
+fn write_histogram(out: &mut impl Write, counts: &[usize]) -> io::Result<()> {
+    let bucket = counts.len().div_ceil(HISTOGRAM_ROWS).max(1);
+    let totals = counts
+        .chunks(bucket)
+        .map(|chunk| chunk.iter().sum::<usize>())
+        .collect::<Vec<_>>();
+    let largest = totals.iter().copied().max().unwrap_or(0).max(1);
+
+    for (idx, total) in totals.into_iter().enumerate() {
+        let start = idx * bucket;
+        let end = (start + bucket).min(counts.len()) - 1;
+        let range = if start == end {
+            start.to_string()
+        } else {
+            format!("{}-{}", start, end)
+        };
+        writeln!(
+            out,
+            "  {:>9} | {:<width$} {}",
+            range,
+            "#".repeat(total * HISTOGRAM_WIDTH / largest),
+            total,
+            width = HISTOGRAM_WIDTH
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Draws the shortest route over the height map, or how far every square is from the
+/// start (part 1) or from the end (part 2)
+fn visualise_day_12(out: &mut impl Write, input: &str, options: &Options) -> anyhow::Result<()> {
+    let height_map = day12::parse_height_map(input)?;
+    if options.distances {
+        return visualise_day_12_distances(out, &height_map, options);
+    }
+
+    let path = match options.part {
+        1 => height_map.shortest_path::<Part1Strategy>(Part1Strategy::start_pos(&height_map)),
+        _ => height_map.shortest_path::<Part2Strategy>(Part2Strategy::start_pos(&height_map)),
+    };
+    let nodes = path.as_ref().map_or(&[][..], |path| &path.nodes);
+
+    write!(out, "{}", height_map.render_path(nodes, options.terminal))?;
+    match &path {
+        Some(path) => writeln!(out, "Part {}: {} steps", options.part, path.steps())?,
+        None => writeln!(out, "Part {}: there is no route", options.part)?,
+    }
+
+    if let Some(image_path) = &options.image {
+        save_image(image_path, &height_map.path_image(nodes))?;
+        writeln!(out, "Saved the image to {:?}", image_path)?;
+    }
+
+    Ok(())
+}
+
+fn visualise_day_12_distances(
+    out: &mut impl Write,
+    height_map: &HeightMap,
+    options: &Options,
+) -> anyhow::Result<()> {
+    let field = match options.part {
+        1 => {
+            writeln!(out, "Steps needed to climb from the start to each square")?;
+            height_map.distance_field::<Part1Strategy>([Part1Strategy::start_pos(height_map)])
+        }
+        _ => {
+            writeln!(out, "Steps needed to climb from each square to the end")?;
+            height_map.distance_field::<Part2Strategy>([Part2Strategy::start_pos(height_map)])
+        }
+    };
+
+    let squares = field.iter().count();
+    let unreachable = field.unreachable().count();
+    writeln!(
+        out,
+        "  {} of {} squares are connected, the farthest being {} steps away",
+        squares - unreachable,
+        squares,
+        field.farthest().unwrap_or(0)
+    )?;
+    if options.part == 2 {
+        writeln!(
+            out,
+            "  {} of {} squares at elevation a are cut off from the end",
+            height_map.cut_off_starts().len(),
+            height_map.lowest_squares().count()
+        )?;
+    }
+
+    writeln!(out)?;
+    write_histogram(out, &field.histogram())?;
+
+    if let Some(image_path) = &options.image {
+        save_image(image_path, &field.heatmap())?;
+        writeln!(out, "Saved the heatmap to {:?}", image_path)?;
+    }
+
+    Ok(())
+}
+
+/// Puts the terminal into a mode where keys can be read as soon as they are pressed, and
+/// puts it back how it was when dropped
+///
+/// Ctrl-C is read as a key too rather than killing the process, as that would leave the
+/// terminal without echo.
+struct RawMode {
+    previous: String,
+}
+
+impl RawMode {
+    fn enable() -> Option<Self> {
+        let output = Command::new("stty")
+            .arg("-g")
+            .stdin(Stdio::inherit())
+            .output()
+            .ok()?;
+        let previous = String::from_utf8(output.stdout).ok()?.trim().to_owned();
+
+        let status = Command::new("stty")
+            .args(["-icanon", "-echo", "-isig", "min", "1"])
+            .stdin(Stdio::inherit())
+            .status()
+            .ok()?;
+        status.success().then_some(Self { previous })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        let _ = Command::new("stty")
+            .arg(&self.previous)
+            .stdin(Stdio::inherit())
+            .status();
+    }
+}
+
+/// Reads keys from stdin on their own thread, so that animations can keep running while
+/// waiting for them
+fn read_keys() -> Receiver<u8> {
+    let (send, receive) = mpsc::channel();
+    thread::spawn(move || {
+        for byte in io::stdin().lock().bytes() {
+            match byte {
+                Ok(byte) if send.send(byte).is_ok() => {}
+                _ => break,
+            }
+        }
+    });
+    receive
+}
+
+/// What the user asked an animation to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Control {
+    TogglePause,
+    Step,
+    JumpToEnd,
+    Quit,
+}
+
+impl Control {
+    fn from_key(key: u8) -> Option<Self> {
+        match key {
+            b' ' | b'p' => Some(Self::TogglePause),
+            b'n' | b's' => Some(Self::Step),
+            b'e' => Some(Self::JumpToEnd),
+            // Ctrl-C, which `RawMode` stops from interrupting the process
+            b'q' | 0x03 => Some(Self::Quit),
+            _ => None,
+        }
+    }
+}
+
+/// Waits until `deadline` for the user to ask for something, if they can
+fn wait_for_control(keys: Option<&Receiver<u8>>, deadline: Instant) -> Option<Control> {
+    let Some(keys) = keys else {
+        thread::sleep(deadline.saturating_duration_since(Instant::now()));
+        return None;
+    };
+
+    loop {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match keys.recv_timeout(timeout) {
+            Ok(key) => {
+                if let Some(control) = Control::from_key(key) {
+                    return Some(control);
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => return None,
+            Err(RecvTimeoutError::Disconnected) => {
+                thread::sleep(timeout);
+                return None;
+            }
+        }
+    }
+}
+
+/// Drops up to `count` grains of sand into the cave, returning how many came to rest and
+/// whether the cave is now full (or the sand now falls out of it)
+fn drop_sand(slice: &mut CaveSlice, count: u32) -> (usize, bool) {
+    let mut grains = 0;
+    for _ in 0..count {
+        if slice.simulate_sand().is_none() {
+            return (grains, true);
+        }
+        grains += 1;
+    }
+    (grains, false)
+}
+
+/// Drops sand into the cave grain by grain, redrawing it as it goes
+///
+/// Part 1 uses the bottomless cave, and part 2 adds the floor. The animation can be
+/// paused, stepped through and skipped to the end when run in a terminal; otherwise, only
+/// the final state of the cave is drawn.
+fn visualise_day_14(out: &mut impl Write, input: &str, options: &Options) -> anyhow::Result<()> {
+    let mut slice = input.parse::<CaveSlice>()?;
+    let variant = match options.part {
+        1 => "bottomless cave",
+        _ => {
+            slice.insert_floor();
+            "cave with a floor"
+        }
+    };
+
+    if !options.terminal {
+        let (grains, _) = drop_sand(&mut slice, u32::MAX);
+        writeln!(out, "{}", slice)?;
+        writeln!(
+            out,
+            "Part {} ({}): {} grains of sand came to rest",
+            options.part, variant, grains
+        )?;
+        return Ok(());
+    }
+
+    let raw_mode = options.interactive.then(RawMode::enable).flatten();
+    let keys = raw_mode.as_ref().map(|_| read_keys());
+
+    // the cursor is only hidden when Ctrl-C can't kill the process before it is shown again
+    let hide_cursor = raw_mode.is_some();
+    write!(out, "{}", CLEAR_SCREEN)?;
+    if hide_cursor {
+        write!(out, "{}", HIDE_CURSOR)?;
+    }
+    let result = animate_day_14(out, &mut slice, variant, keys.as_ref(), options);
+    if hide_cursor {
+        write!(out, "{}", SHOW_CURSOR)?;
+    }
+    out.flush()?;
+
+    Ok(result?)
+}
+
+fn animate_day_14(
+    out: &mut impl Write,
+    slice: &mut CaveSlice,
+    variant: &str,
+    keys: Option<&Receiver<u8>>,
+    options: &Options,
+) -> io::Result<()> {
+    let frame_time =
+        Duration::from_secs_f64(1.0 / options.fps.unwrap_or(DEFAULT_FPS).max(1) as f64);
+    let batch_size = options.batch.unwrap_or(DEFAULT_BATCH);
+    let mut grains = 0;
+    let mut paused = false;
+    let mut finished = false;
+
+    loop {
+        let state = if finished {
+            "finished"
+        } else if paused {
+            "paused"
+        } else {
+            "running"
+        };
+        writeln!(out, "{}{}", CURSOR_HOME, slice)?;
+        writeln!(
+            out,
+            "Part {} ({}): {} grains of sand at rest [{}]{}",
+            options.part, variant, grains, state, CLEAR_LINE
+        )?;
+        if keys.is_some() && !finished {
+            writeln!(
+                out,
+                "[space] pause  [n] step  [e] jump to the end  [q] quit{}",
+                CLEAR_LINE
+            )?;
+        }
+        out.flush()?;
+
+        if finished {
+            return Ok(());
+        }
+
+        let batch = match wait_for_control(keys, Instant::now() + frame_time) {
+            Some(Control::TogglePause) => {
+                paused = !paused;
+                0
+            }
+            Some(Control::Step) => {
+                paused = true;
+                batch_size
+            }
+            Some(Control::JumpToEnd) => u32::MAX,
+            Some(Control::Quit) => return Ok(()),
+            None if paused => 0,
+            None => batch_size,
+        };
+
+        let (dropped, full) = drop_sand(slice, batch);
+        grains += dropped;
+        finished = full;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY_14_DEMO: &str = "\
+498,4 -> 498,6 -> 496,6
+503,4 -> 502,4 -> 502,9 -> 494,9
+";
+
+    fn options(part: u8) -> Options {
+        Options {
+            part,
+            image: None,
+            terminal: false,
+            interactive: false,
+            distances: false,
+            fps: None,
+            batch: None,
+        }
+    }
+
+    #[test]
+    fn draws_the_final_cave_when_not_in_a_terminal() {
+        for (part, expected) in [(1, "24 grains"), (2, "93 grains")] {
+            let mut out = Vec::new();
+            visualise(&mut out, 14, DAY_14_DEMO, &options(part)).unwrap();
+
+            let out = String::from_utf8(out).unwrap();
+            assert!(out.contains(expected), "{}", out);
+            assert!(!out.contains(CURSOR_HOME));
+        }
+    }
+
+    #[test]
+    fn rejects_options_for_other_days() {
+        let with_image = Options {
+            image: Some("cave.ppm".into()),
+            ..options(1)
+        };
+        let error = visualise(&mut Vec::new(), 14, DAY_14_DEMO, &with_image).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "--image can't be used when visualising day 14"
+        );
+
+        let with_fps = Options {
+            fps: Some(10),
+            ..options(1)
+        };
+        assert!(visualise(
+            &mut Vec::new(),
+            12,
+            "SbcdefghijklmnopqrstuvwxyzE",
+            &with_fps
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn groups_long_histograms_into_ranges() {
+        let mut out = Vec::new();
+        write_histogram(&mut out, &[1; 30]).unwrap();
+
+        let out = String::from_utf8(out).unwrap();
+        assert_eq!(out.lines().count(), 15);
+        assert!(out
+            .lines()
+            .next()
+            .unwrap()
+            .trim_start()
+            .starts_with("0-1 | ###"));
+    }
+
+    #[test]
+    fn maps_keys_to_controls() {
+        assert_eq!(Control::from_key(b' '), Some(Control::TogglePause));
+        assert_eq!(Control::from_key(b'n'), Some(Control::Step));
+        assert_eq!(Control::from_key(b'e'), Some(Control::JumpToEnd));
+        assert_eq!(Control::from_key(0x03), Some(Control::Quit));
+        assert_eq!(Control::from_key(b'x'), None);
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    time::SystemTime,
+};
+
+use anyhow::Context;
+
+/// The modification times of a set of files, used to notice when any of them change
+///
+/// Directories are expanded to the files directly inside them, so files being added or
+/// removed is noticed too. Paths that don't exist are remembered as missing.
+#[derive(Debug)]
+pub struct Snapshot {
+    paths: Vec<PathBuf>,
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Snapshot {
+    pub fn take(paths: Vec<PathBuf>) -> Self {
+        let files = list_files(&paths);
+        Self { paths, files }
+    }
+
+    /// Whether any of the files have changed since the snapshot was taken
+    pub fn has_changed(&self) -> bool {
+        list_files(&self.paths) != self.files
+    }
+}
+
+fn list_files(paths: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut files = Vec::new();
+    for path in paths {
+        match fs::read_dir(path) {
+            Ok(entries) => {
+                let mut entries = entries
+                    .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                    .collect::<Vec<_>>();
+                entries.sort();
+                files.extend(entries.into_iter().map(|path| {
+                    let modified = modified(&path);
+                    (path, modified)
+                }));
+            }
+            Err(_) => files.push((path.clone(), modified(path))),
+        }
+    }
+
+    files
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Rebuilds the runner, and if that succeeds, replaces this process with the new build
+/// run with the same arguments
+///
+/// Only returns if the build failed, in which case cargo will already have shown why.
+pub fn rebuild_and_restart(manifest_dir: &Path) -> anyhow::Result<()> {
+    let cargo = env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let manifest = manifest_dir.join("Cargo.toml");
+    let profile: &[&str] = if cfg!(debug_assertions) {
+        &[]
+    } else {
+        &["--release"]
+    };
+
+    let status = Command::new(&cargo)
+        .args(["build", "--quiet", "--manifest-path"])
+        .arg(&manifest)
+        .args(profile)
+        .status()
+        .context("could not run cargo")?;
+    if !status.success() {
+        return Ok(());
+    }
+
+    let mut command = Command::new(&cargo);
+    command
+        .args(["run", "--quiet", "--manifest-path"])
+        .arg(&manifest)
+        .args(profile)
+        .arg("--")
+        .args(env::args_os().skip(1));
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::CommandExt;
+        Err(command.exec()).context("could not restart the runner")
+    }
+
+    #[cfg(not(unix))]
+    {
+        let status = command.status().context("could not restart the runner")?;
+        std::process::exit(status.code().unwrap_or(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notices_files_being_created_and_removed() {
+        let dir = env::temp_dir().join(format!("aoc-watch-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("day_1.txt");
+
+        let snapshot = Snapshot::take(vec![file.clone(), dir.clone()]);
+        assert!(!snapshot.has_changed());
+
+        fs::write(&file, "1000").unwrap();
+        assert!(snapshot.has_changed());
+
+        let snapshot = Snapshot::take(vec![dir.clone()]);
+        fs::remove_file(&file).unwrap();
+        assert!(snapshot.has_changed());
+
+        fs::remove_dir(&dir).unwrap();
+    }
+}
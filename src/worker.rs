@@ -0,0 +1,458 @@
+use std::{
+    env,
+    io::{self, BufRead, BufReader, Read, Write},
+    process::{Command, ExitStatus, Stdio},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::answer::Answer;
+use crate::panics::{self, Panic};
+use crate::registry::{self, DayEntry, ParsedInput, Part};
+
+/// How often a supervised worker is checked on while it runs
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// How a single part finished
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum Outcome {
+    Answer(Answer),
+    Error(String),
+    Panicked(Panic),
+    /// The worker was killed for running longer than the timeout
+    TimedOut(Duration),
+    /// The worker died without reporting back, for example by running out of memory
+    Died(String),
+}
+
+/// The outcome of running a part, along with how long it took
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PartRun {
+    pub outcome: Outcome,
+    pub duration: Duration,
+    /// The timings of every `--bench` iteration, starting with the first run
+    pub samples: Vec<Duration>,
+    /// How long parsing took, when the part was run in a worker that parsed the input itself
+    pub parse_duration: Option<Duration>,
+    /// The timings of every `--bench` parse, when the worker parsed the input itself
+    pub parse_samples: Vec<Duration>,
+}
+
+impl PartRun {
+    /// A part that never got to run, because parsing its input failed
+    pub fn not_run(outcome: Outcome) -> Self {
+        Self {
+            outcome,
+            duration: Duration::ZERO,
+            samples: Vec::new(),
+            parse_duration: None,
+            parse_samples: Vec::new(),
+        }
+    }
+}
+
+/// Limits placed on a supervised worker
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Limits {
+    pub timeout: Option<Duration>,
+    /// The most memory (in bytes) the worker may map
+    pub memory: Option<u64>,
+}
+
+impl Limits {
+    pub fn is_set(&self) -> bool {
+        self.timeout.is_some() || self.memory.is_some()
+    }
+}
+
+fn outcome_of(result: Result<anyhow::Result<Answer>, Panic>) -> Outcome {
+    match result {
+        Ok(Ok(answer)) => Outcome::Answer(answer),
+        Ok(Err(err)) => Outcome::Error(format!("{:#}", err)),
+        Err(panic) => Outcome::Panicked(panic),
+    }
+}
+
+/// Parses a day's input in this process, turning any failure into the outcome that each
+/// of its parts should report
+pub fn parse(entry: &DayEntry, input: &str) -> (Result<Box<dyn ParsedInput>, Outcome>, Duration) {
+    let start = Instant::now();
+    let result = panics::catch(|| (entry.parse)(input));
+    let duration = start.elapsed();
+
+    let result = match result {
+        Ok(Ok(parsed)) => Ok(parsed),
+        Ok(Err(err)) => Err(Outcome::Error(format!("{:#}", err))),
+        Err(panic) => Err(Outcome::Panicked(panic)),
+    };
+    (result, duration)
+}
+
+/// Parses the input until it has been parsed `bench` times in all, including the first
+/// parse that took `first`, and returns how long each took
+///
+/// Nothing is timed if `bench` is 0.
+pub fn bench_parse(entry: &DayEntry, input: &str, first: Duration, bench: u32) -> Vec<Duration> {
+    if bench == 0 {
+        return Vec::new();
+    }
+
+    let mut samples = vec![first];
+    for _ in 1..bench {
+        let start = Instant::now();
+        let _ = panics::catch(|| (entry.parse)(input));
+        samples.push(start.elapsed());
+    }
+    samples
+}
+
+/// Runs a part in this process, then keeps running it until it has run `bench` times in
+/// all for timings
+pub fn run_part(solve: Part, input: &dyn ParsedInput, bench: u32) -> PartRun {
+    let mut run = solve_once(solve, input);
+    bench_part(solve, input, bench, &mut run);
+    run
+}
+
+fn solve_once(solve: Part, input: &dyn ParsedInput) -> PartRun {
+    let start = Instant::now();
+    let outcome = outcome_of(panics::catch(|| solve(input)));
+    PartRun {
+        outcome,
+        duration: start.elapsed(),
+        samples: Vec::new(),
+        parse_duration: None,
+        parse_samples: Vec::new(),
+    }
+}
+
+/// Keeps running a part that has already been run once until it has run `bench` times in
+/// all, unless that first run didn't find an answer
+fn bench_part(solve: Part, input: &dyn ParsedInput, bench: u32, run: &mut PartRun) {
+    if bench == 0 || !matches!(run.outcome, Outcome::Answer(_)) {
+        return;
+    }
+
+    run.samples.push(run.duration);
+    for _ in 1..bench {
+        let start = Instant::now();
+        let _ = panics::catch(|| solve(input));
+        run.samples.push(start.elapsed());
+    }
+}
+
+/// The body of the hidden `worker` subcommand: parses the input given on stdin and runs one
+/// part on it, writing the result as a line of JSON on stdout
+///
+/// With `bench` set, the result is written once the part has first been solved, so that
+/// the supervisor can stop timing it, and again with the timings once benching finishes.
+pub fn serve(day: u8, part: u8, bench: u32) -> anyhow::Result<()> {
+    let entry = registry::find(crate::DAYS, day)
+        .with_context(|| format!("day {} has not been implemented", day))?;
+
+    let mut input = String::new();
+    io::stdin().read_to_string(&mut input)?;
+
+    let (parsed, parse_duration) = parse(entry, &input);
+    let mut run = match &parsed {
+        Ok(parsed) => solve_once(entry.part(part), &**parsed),
+        Err(outcome) => PartRun::not_run(outcome.clone()),
+    };
+    run.parse_duration = Some(parse_duration);
+    report(&run)?;
+
+    if let (Ok(parsed), 1..) = (&parsed, bench) {
+        bench_part(entry.part(part), &**parsed, bench, &mut run);
+        run.parse_samples = bench_parse(entry, &input, parse_duration, bench);
+        report(&run)?;
+    }
+    Ok(())
+}
+
+/// Writes a result for the supervisor to read
+fn report(run: &PartRun) -> anyhow::Result<()> {
+    let mut out = io::stdout().lock();
+    // start on a fresh line, in case the solution printed anything without a newline
+    writeln!(out)?;
+    writeln!(out, "{}", serde_json::to_string(run)?)?;
+    out.flush()?;
+    Ok(())
+}
+
+/// Runs a part in a separate worker process, killing it if it breaks the limits
+pub fn supervise(
+    entry: &DayEntry,
+    part: u8,
+    input: &str,
+    bench: u32,
+    limits: Limits,
+) -> anyhow::Result<PartRun> {
+    let exe = env::current_exe().context("could not find the runner executable")?;
+    let worker_args = [
+        "worker".to_owned(),
+        entry.day.to_string(),
+        part.to_string(),
+        format!("--bench={}", bench),
+    ];
+
+    let command = match limits.memory {
+        // `ulimit -v` works in KiB, and applies to everything the shell then execs
+        Some(bytes) if cfg!(unix) => {
+            let mut command = Command::new("sh");
+            command
+                .arg("-c")
+                .arg("ulimit -v \"$1\" && shift && exec \"$@\"")
+                .arg("sh")
+                .arg((bytes / 1024).to_string())
+                .arg(&exe);
+            command
+        }
+        Some(_) => anyhow::bail!("memory limits are only supported on unix"),
+        None => Command::new(&exe),
+    };
+
+    run_worker(command, &worker_args, input, limits)
+}
+
+/// Starts a worker with `args`, gives it `input` and waits for it to report a result
+///
+/// The worker is killed if it hasn't reported a result before the timeout, but once it
+/// has, it may keep running for as long as it needs to finish benching.
+fn run_worker(
+    mut command: Command,
+    args: &[String],
+    input: &str,
+    limits: Limits,
+) -> anyhow::Result<PartRun> {
+    let mut child = command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("could not start a worker")?;
+
+    // the pipes are fed and drained on their own threads, so that neither side can block
+    // the other once a pipe's buffer fills up
+    let mut stdin = child.stdin.take().unwrap();
+    let input = input.to_owned();
+    thread::spawn(move || stdin.write_all(input.as_bytes()));
+    let stdout = read_lines_in_background(child.stdout.take().unwrap());
+    let stderr = read_in_background(child.stderr.take().unwrap());
+
+    let start = Instant::now();
+    let mut result = None;
+    let status = loop {
+        result = stdout
+            .try_iter()
+            .filter_map(|line| as_result(&line))
+            .last()
+            .or(result);
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+
+        match limits.timeout {
+            Some(timeout) if result.is_none() && start.elapsed() > timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(PartRun {
+                    duration: start.elapsed(),
+                    ..PartRun::not_run(Outcome::TimedOut(timeout))
+                });
+            }
+            _ => thread::sleep(POLL_INTERVAL),
+        }
+    };
+
+    // the rest of the output is sent once the pipe closes, which it does as the worker exits
+    result = stdout
+        .iter()
+        .filter_map(|line| as_result(&line))
+        .last()
+        .or(result);
+    let stderr = stderr.join().unwrap_or_default();
+
+    Ok(match result {
+        Some(run) if status.success() => run,
+        _ => PartRun {
+            duration: start.elapsed(),
+            ..PartRun::not_run(Outcome::Died(describe_death(status, &stderr, limits)))
+        },
+    })
+}
+
+/// Reads a result written by `report`, ignoring anything else the part printed
+fn as_result(line: &str) -> Option<PartRun> {
+    serde_json::from_str(line).ok()
+}
+
+/// Sends each line written to `pipe` as soon as it is read
+fn read_lines_in_background(pipe: impl Read + Send + 'static) -> Receiver<String> {
+    let (send, receive) = mpsc::channel();
+    thread::spawn(move || {
+        let mut pipe = BufReader::new(pipe);
+        let mut line = Vec::new();
+        while matches!(pipe.read_until(b'\n', &mut line), Ok(1..)) {
+            if send
+                .send(String::from_utf8_lossy(&line).into_owned())
+                .is_err()
+            {
+                break;
+            }
+            line.clear();
+        }
+    });
+    receive
+}
+
+fn read_in_background(mut pipe: impl Read + Send + 'static) -> thread::JoinHandle<String> {
+    thread::spawn(move || {
+        let mut contents = String::new();
+        let _ = pipe.read_to_string(&mut contents);
+        contents
+    })
+}
+
+fn describe_death(status: ExitStatus, stderr: &str, limits: Limits) -> String {
+    if let Some(memory) = limits.memory {
+        if stderr.contains("memory allocation of") {
+            return format!("ran out of memory (limit {})", format_size(memory));
+        }
+    }
+
+    match stderr.lines().find(|line| !line.trim().is_empty()) {
+        Some(line) => format!("worker {}: {}", status, line.trim()),
+        None => format!("worker {}", status),
+    }
+}
+
+/// Parses a duration like `500ms`, `2s`, `1.5m` or `30` (seconds)
+pub fn parse_duration(text: &str) -> Result<Duration, String> {
+    let text = text.trim();
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("expected a number followed by ms, s or m, found {:?}", text))?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        _ => return Err(format!("unknown unit {:?}, expected ms, s or m", unit)),
+    };
+
+    Duration::try_from_secs_f64(seconds).map_err(|_| format!("{:?} is too long", text))
+}
+
+/// Parses a size in bytes like `512M`, `2G`, `64K` or `1048576`
+pub fn parse_size(text: &str) -> Result<u64, String> {
+    let text = text.trim();
+    let (number, multiplier) = match text.char_indices().last() {
+        Some((idx, 'K' | 'k')) => (&text[..idx], 1 << 10),
+        Some((idx, 'M' | 'm')) => (&text[..idx], 1 << 20),
+        Some((idx, 'G' | 'g')) => (&text[..idx], 1 << 30),
+        _ => (text, 1),
+    };
+
+    let number = number
+        .parse::<u64>()
+        .map_err(|_| format!("expected a size like 512M or 2G, found {:?}", text))?;
+    number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("{:?} is too big", text))
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b >= 1 << 30 && b % (1 << 30) == 0 => format!("{}G", b >> 30),
+        b if b >= 1 << 20 && b % (1 << 20) == 0 => format!("{}M", b >> 20),
+        b if b >= 1 << 10 && b % (1 << 10) == 0 => format!("{}K", b >> 10),
+        b => format!("{} bytes", b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_durations() {
+        assert_eq!(parse_duration("500ms"), Ok(Duration::from_millis(500)));
+        assert_eq!(parse_duration("2s"), Ok(Duration::from_secs(2)));
+        assert_eq!(parse_duration("30"), Ok(Duration::from_secs(30)));
+        assert_eq!(parse_duration("1.5m"), Ok(Duration::from_secs(90)));
+        assert!(parse_duration("2h").is_err());
+        assert!(parse_duration("s").is_err());
+        assert!(parse_duration("99999999999999999999999").is_err());
+    }
+
+    #[test]
+    fn benches_include_the_first_run() {
+        let solve: Part = |_| Ok(Answer::from(1_u8));
+
+        let run = run_part(solve, &(), 3);
+        assert_eq!(run.outcome, Outcome::Answer(Answer::from(1_u8)));
+        assert_eq!(run.samples.len(), 3);
+        assert_eq!(run.samples[0], run.duration);
+        assert!(run_part(solve, &(), 0).samples.is_empty());
+    }
+
+    /// A stand-in for a worker, which writes each of `lines` with a pause between them
+    #[cfg(unix)]
+    fn slow_worker(lines: &[&PartRun]) -> (Command, Vec<String>) {
+        let mut command = Command::new("sh");
+        command
+            .arg("-c")
+            .arg("for line in \"$@\"; do sleep 0.3; printf '%s\\n' \"$line\"; done");
+
+        let mut args = vec!["sh".to_owned()];
+        args.extend(lines.iter().map(|run| serde_json::to_string(run).unwrap()));
+        (command, args)
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn timeouts_only_cover_the_first_solve_when_benching() {
+        let solve: Part = |_| Ok(Answer::from(1_u8));
+        let first = solve_once(solve, &());
+        let benched = run_part(solve, &(), 3);
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(450)),
+            memory: None,
+        };
+
+        // the first result arrives within the timeout and benching finishes after it
+        let (command, args) = slow_worker(&[&first, &benched]);
+        let run = run_worker(command, &args, "", limits).unwrap();
+        assert_eq!(run.outcome, Outcome::Answer(Answer::from(1_u8)));
+        assert_eq!(run.samples.len(), 3);
+
+        // the first result doesn't arrive in time
+        let (command, args) = slow_worker(&[&PartRun::not_run(Outcome::Died(String::new()))]);
+        let limits = Limits {
+            timeout: Some(Duration::from_millis(100)),
+            ..limits
+        };
+        let run = run_worker(command, &args, "", limits).unwrap();
+        assert_eq!(run.outcome, Outcome::TimedOut(Duration::from_millis(100)));
+    }
+
+    #[test]
+    fn parses_sizes() {
+        assert_eq!(parse_size("64K"), Ok(64 * 1024));
+        assert_eq!(parse_size("512M"), Ok(512 * 1024 * 1024));
+        assert_eq!(parse_size("2g"), Ok(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("1000"), Ok(1000));
+        assert!(parse_size("lots").is_err());
+        assert!(parse_size("99999999999999G").is_err());
+        assert_eq!(format_size(512 * 1024 * 1024), "512M");
+    }
+}